@@ -19,7 +19,7 @@ fn main() {
         match maybe_line {
             None => break,
             Some(user_input) => {
-                if user_input.len() == 0 {
+                if user_input.is_empty() {
                     continue
                 } else {
                     cmd.write_line(&user_input);
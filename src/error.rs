@@ -37,6 +37,13 @@ impl Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{:?} error - {}", self.type_, self.message)
+        write!(f, "{:?} error - {}", self.type_, self.message)?;
+
+        // lines are counted from one, zero means we never found out where the error happened
+        if self.line_number > 0 {
+            write!(f, " at line {}, column {}", self.line_number, self.character_number)?;
+        }
+
+        Ok(())
     }
 }
\ No newline at end of file
@@ -1,27 +1,66 @@
-use crate::reader::tokenizer::Token;
 use crate::types::list::List;
-use crate::exec::eval::{eval_ast};
+use crate::exec::eval::eval_args;
 use std::rc::Rc;
-use std::borrow::BorrowMut;
 use crate::types::env::Scope;
-use crate::types::ast::{LispValue, Lambda};
+use crate::types::ast::{LispValue, Closure};
+use crate::exec::core_recursive::{RECUR_TARGET, eval_body};
+use crate::exec::destructure::{bind, bind_recur, recur_arity, param_count};
 
-pub fn create_closure(tokens: Vec<Token>, expr: LispValue)
-    -> Lambda {
+/// one parameter list of a function together with the forms of its body, which run as an implicit do
+#[derive(Clone)]
+pub struct Arity {
+    pub params: List,
+    pub body: List
+}
 
-    return Rc::new(move |args: &List, env: &mut Scope| {
+impl Arity {
+    fn accepts(&self, count: usize) -> bool {
+        match param_count(&self.params) {
+            (fixed, false) => count == fixed,
+            (fixed, true) => count >= fixed
+        }
+    }
+}
 
-        let mut function_scope = env.new_scope();
+/// builds the closure for a function with one or more arities. calls go to the arity whose
+/// parameter count matches exactly, or otherwise to the one with a rest parameter.
+/// functions are lexically scoped: the body runs in a child of the scope the function was created
+/// in rather than of the caller's, so a function that outlives that scope, say one returned from a
+/// let or held by a lazy seq, still sees its bindings, and a caller's let can't shadow them.
+pub fn create_closure(arities: Vec<Arity>, name: Option<String>) -> Closure {
+    Rc::new(move |args: &List, env: &mut Scope, definition: &Scope| {
 
-        args.items()
-            .iter()
-            .skip(1)
-            .map(|x| {eval_ast(x, env)})
-            .enumerate()
-            .for_each(|(i, result) | {
-                function_scope.borrow_mut().set(tokens[i].get_text().clone(), result)
-            });
+        let values = match eval_args(args, env) {
+            Ok(values) => values,
+            Err(e) => return e
+        };
 
-        eval_ast(&expr, &mut function_scope)
+        let arity = match arities.iter().find(|a| !param_count(&a.params).1 && a.accepts(values.len()))
+            .or_else(|| arities.iter().find(|a| a.accepts(values.len()))) {
+            Some(arity) => arity,
+            None => return LispValue::Error(format!("wrong number of args ({}) passed to {}",
+                                                    values.len(), name.as_deref().unwrap_or("lambda")))
+        };
+
+        let mut function_scope = definition.new_scope();
+
+        // the argument list is destructured against the parameter list as a whole
+        if let Err(e) = bind(&LispValue::List(arity.params.clone()), LispValue::List(List::from_vec(values)), &mut function_scope) {
+            return LispValue::Error(e)
+        }
+
+        function_scope.set(RECUR_TARGET.to_string(), LispValue::Int(recur_arity(&arity.params) as i64));
+
+        // a recur in tail position hands back the next arguments, rebinding them here keeps the stack flat
+        loop {
+            match eval_body(&arity.body, 0, &mut function_scope) {
+                LispValue::Recur(values) => {
+                    if let Err(e) = bind_recur(&arity.params, values, &mut function_scope) {
+                        return LispValue::Error(e)
+                    }
+                },
+                result => return result
+            }
+        }
     })
-}
\ No newline at end of file
+}
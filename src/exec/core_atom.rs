@@ -73,11 +73,9 @@ pub fn apply_swap(list: &List, env: &mut Scope) -> LispValue {
     if let LispValue::Atom(val) = maybe_atom {
         if let LispValue::Function(f) = maybe_f {
 
-            let mut input_list = List::new();
-            input_list.push(LispValue::Nil);
-            input_list.push(val.as_ref().borrow().clone());
+            let input_list = List::from_vec(vec![LispValue::Nil, val.as_ref().borrow().clone()]);
 
-            let new_value = f(&input_list, env);
+            let new_value = f.call(&input_list, env);
 
             if let LispValue::Error(e) = new_value {
                 return LispValue::Error(e)
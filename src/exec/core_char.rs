@@ -0,0 +1,86 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::printer::pr_str;
+use crate::exec::eval::eval_ast;
+use crate::types::numeric::normalize;
+use crate::arg_return;
+use num_bigint::BigInt;
+use num_traits::FromPrimitive;
+use std::convert::TryFrom;
+
+/// (char n) is the character with the code point n
+pub fn apply_char(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(char, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        c @ LispValue::Char(_) => c,
+        LispValue::Int(i) => match u32::try_from(i).ok().and_then(std::char::from_u32) {
+            Some(c) => LispValue::Char(c),
+            None => LispValue::Error(format!("{} is not a valid character code", i))
+        },
+        other => LispValue::Error(format!("cannot make a character out of {}", pr_str(&other, true)))
+    }
+}
+
+/// (int x) is the code point of a character, numbers are truncated towards zero
+pub fn apply_int(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(int, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        LispValue::Char(c) => LispValue::Int(c as i64),
+        i @ LispValue::Int(_) | i @ LispValue::BigInt(_) => i,
+        LispValue::Ratio(r) => normalize(r.trunc().to_integer()),
+        LispValue::Float(f) => match BigInt::from_f64(f.trunc()) {
+            Some(b) => normalize(b),
+            None => LispValue::Error(format!("cannot convert {:?} to an int", f))
+        },
+        other => LispValue::Error(format!("cannot convert {} to an int", pr_str(&other, true)))
+    }
+}
+
+pub fn apply_is_char(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_char, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        value => LispValue::Boolean(matches!(value, LispValue::Char(_)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn char_literals_round_trip() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(pr-str \\a \\newline \\space \\λ \\u03bb \\( \\u0000)", &mut env).to_string(),
+                   "\\a \\newline \\space \\λ \\λ \\( \\u0000");
+        assert_eq!(eval("(= (read-string (pr-str \\tab)) \\tab)", &mut env).to_string(), "true");
+        assert_eq!(eval("(str \\a \\space \\b)", &mut env).to_string(), "a b");
+        assert!(matches!(read_string(&Tokenizer::new(), "\\bogus".to_string()), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn conversions() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(int \\a)", &mut env).to_string(), "97");
+        assert_eq!(eval("(char 955)", &mut env).to_string(), "λ");
+        assert_eq!(eval("(char? \\a)", &mut env).to_string(), "true");
+        assert_eq!(eval("(char? \"a\")", &mut env).to_string(), "false");
+        assert_eq!(eval("(int 2.7)", &mut env).to_string(), "2");
+        assert_eq!(eval("(int -7/2)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(pr-str (seq \"hé\"))", &mut env).to_string(), "(\\h \\é)");
+        assert_eq!(eval("(seq \"\")", &mut env).to_string(), "nil");
+        assert!(matches!(eval("(char -1)", &mut env), LispValue::Error(_)));
+    }
+}
@@ -1,59 +1,134 @@
 use crate::types::list::List;
-use crate::exec::eval::{eval_ast};
+use crate::exec::eval::{eval_args};
 use crate::types::env::Scope;
-use crate::types::ast::LispValue;
+use crate::types::ast::{LispValue, hash_value};
+use crate::arg_return;
+use crate::types::numeric::{to_float, numeric_ordering};
+use std::cmp::Ordering;
 
 
-macro_rules! comp {
-    ($comp:tt, $type:ident, $left:expr, $right:expr) => {
-        if let LispValue::$type(unwrapped_r) = $right {
-            LispValue::Boolean($left $comp unwrapped_r)
-        } else {
-            LispValue::Boolean(false)
-        }
+fn is_number(value: &LispValue) -> bool {
+    to_float(value).is_some()
+}
+
+/// orders two values of a comparable kind: numbers, strings and keywords (lexicographically),
+/// booleans (false first) and nil, which sorts before everything else. this is the total order of
+/// compare, < and friends go through ordered_values, which doesn't take nil.
+/// Ok(None) means both are numbers but unordered, which only happens with NaN.
+pub fn compare_values(l: &LispValue, r: &LispValue) -> Result<Option<Ordering>, String> {
+    match (l, r) {
+        (LispValue::Nil, LispValue::Nil) => Ok(Some(Ordering::Equal)),
+        (LispValue::Nil, _) => Ok(Some(Ordering::Less)),
+        (_, LispValue::Nil) => Ok(Some(Ordering::Greater)),
+        (LispValue::String(a), LispValue::String(b)) => Ok(Some(a.cmp(b))),
+        (LispValue::Keyword(a), LispValue::Keyword(b)) => Ok(Some(a.cmp(b))),
+        (LispValue::Char(a), LispValue::Char(b)) => Ok(Some(a.cmp(b))),
+        (LispValue::Boolean(a), LispValue::Boolean(b)) => Ok(Some(a.cmp(b))),
+        (l, r) if is_number(l) && is_number(r) => Ok(numeric_ordering(l, r)),
+        (l, r) => Err(format!("cannot compare {} and {}", l, r))
+    }
+}
+
+/// the ordering < > <= and >= test, nil isn't less or greater than anything so it's an error
+fn ordered_values(l: &LispValue, r: &LispValue) -> Result<Option<Ordering>, String> {
+    match (l, r) {
+        (LispValue::Nil, other) | (other, LispValue::Nil) => Err(format!("cannot compare nil and {}", other)),
+        (l, r) => compare_values(l, r)
     }
 }
 
+/// chained comparisons hold when every neighbouring pair passes, (< 1 2 3) is (and (< 1 2) (< 2 3))
 macro_rules! comp_op {
     ($func:ident, $op:tt) => {
         pub fn $func(list: &List, env: &mut Scope) -> LispValue {
-            if list.len() != 3 {
-                return LispValue::Error(stringify!($op works with exactly two items to compare).to_string());
+            if list.len() < 2 {
+                return LispValue::Error(format!("{} needs at least 1 argument", stringify!($op)));
             }
 
-            let l = eval_ast(&list[1], env);
-            let r = eval_ast(&list[2], env);
-
-            match l {
-                LispValue::Error(s) => LispValue::Error(s),
-                LispValue::Float(f) => comp!($op, Float, f, r),
-                LispValue::Int(i) => comp!($op, Int, i, r),
-                _ => LispValue::Boolean(false)
+            let args = match eval_args(list, env) {
+                Err(e) => return e,
+                Ok(args) => args
+            };
 
+            for pair in args.windows(2) {
+                match ordered_values(&pair[0], &pair[1]) {
+                    Err(e) => return LispValue::Error(e),
+                    // Less < Equal < Greater, so comparing the ordering against Equal applies the operator
+                    Ok(Some(ordering)) if ordering $op Ordering::Equal => (),
+                    Ok(_) => return LispValue::Boolean(false)
+                }
             }
+
+            LispValue::Boolean(true)
         }
 
     }
 }
 
 
+fn all_equal(list: &List, env: &mut Scope) -> Result<bool, LispValue> {
+    if list.len() < 2 {
+        return Err(LispValue::Error("equals needs at least one item to compare".to_string()));
+    }
+
+    let args = eval_args(list, env)?;
+
+    Ok(args.windows(2).all(|pair| pair[0] == pair[1]))
+}
+
 pub fn apply_equals(list: &List, env: &mut Scope) -> LispValue {
+    match all_equal(list, env) {
+        Err(e) => e,
+        Ok(b) => LispValue::Boolean(b)
+    }
+}
+
+pub fn apply_not_equals(list: &List, env: &mut Scope) -> LispValue {
+    match all_equal(list, env) {
+        Err(e) => e,
+        Ok(b) => LispValue::Boolean(!b)
+    }
+}
+
+/// values without identity (numbers, strings, keywords, ...) can't be told apart once they are equal,
+/// lists, atoms and functions are only identical when they are the same object.
+fn identical(l: &LispValue, r: &LispValue) -> bool {
+    match (l, r) {
+        (LispValue::List(a), LispValue::List(b)) => a.ptr_eq(b),
+        (LispValue::Atom(_), LispValue::Atom(_))
+        | (LispValue::Function(_), LispValue::Function(_))
+        | (LispValue::Macro(_), LispValue::Macro(_)) => l == r,
+        _ => std::mem::discriminant(l) == std::mem::discriminant(r) && l == r
+    }
+}
+
+pub fn apply_identical(list: &List, env: &mut Scope) -> LispValue {
     if list.len() != 3 {
-        return LispValue::Error("equals needs two comparisons".to_string());
+        return LispValue::Error("identical? takes 2 args".to_string());
     }
 
-    let l = eval_ast(&list[1], env);
-    let r = eval_ast(&list[2], env);
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Boolean(identical(&args[0], &args[1]))
+    }
+}
 
-    match l {
-        LispValue::Error(s) => LispValue::Error(s),
-        LispValue::Float(f) => comp!(==, Float, f, r),
-        LispValue::Int(i) => comp!(==, Int, i, r),
-        LispValue::Boolean(b) => comp!(==, Boolean, b, r),
-        LispValue::String(s) => comp!(==, String, s, r),
-        _ => LispValue::Boolean(false)
+/// (compare a b) is -1, 0 or 1
+pub fn apply_compare(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() != 3 {
+        return LispValue::Error("compare takes 2 args".to_string());
     }
 
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    match compare_values(&args[0], &args[1]) {
+        Err(e) => LispValue::Error(e),
+        Ok(Some(ordering)) => LispValue::Int(ordering as i64),
+        Ok(None) => LispValue::Error("cannot compare NaN".to_string())
+    }
 }
 
 
@@ -61,3 +136,114 @@ comp_op!(apply_less_than, <);
 comp_op!(apply_less_than_equals, <=);
 comp_op!(apply_greater_than, >);
 comp_op!(apply_greater_than_equals, >=);
+
+/// (hash x) is the structural hash of x, equal values have equal hashes
+pub fn apply_hash(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(hash, 1, list);
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Int(hash_value(&args[0]) as i64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    fn is_true(code: &str, env: &mut Scope) -> bool {
+        matches!(eval(code, env), LispValue::Boolean(true))
+    }
+
+    #[test]
+    fn chained_comparisons() {
+        let mut env = Scope::new();
+
+        assert!(is_true("(< 1 2 3)", &mut env));
+        assert!(!is_true("(< 1 3 2)", &mut env));
+        assert!(is_true("(<= 1 1 2)", &mut env));
+        assert!(is_true("(> 3 2.5 1)", &mut env));
+        assert!(is_true("(< 1)", &mut env));
+        assert!(is_true("(< \"abc\" \"abd\")", &mut env));
+        assert!(is_true("(> :b :a)", &mut env));
+        assert!(is_true("(= 1 1 1)", &mut env));
+        assert!(is_true("(not= 1 2)", &mut env));
+        assert!(matches!(eval("(< 1 \"a\")", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn comparisons_need_an_argument() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(<)", &mut env).to_string(), "error - < needs at least 1 argument");
+        assert_eq!(eval("(>=)", &mut env).to_string(), "error - >= needs at least 1 argument");
+    }
+
+    #[test]
+    fn nil_is_only_ordered_by_compare() {
+        let mut env = Scope::new();
+
+        assert!(matches!(eval("(< nil 1)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(>= 1 nil)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(<= nil nil)", &mut env), LispValue::Error(_)));
+        assert_eq!(eval("(compare nil 1)", &mut env).to_string(), "-1");
+        assert_eq!(eval("(compare nil nil)", &mut env).to_string(), "0");
+    }
+
+    #[test]
+    fn structural_equality() {
+        let mut env = Scope::new();
+
+        assert!(is_true("(= (list 1 (list 2 \"x\")) (list 1 (list 2 \"x\")))", &mut env));
+        assert!(is_true("(= nil nil)", &mut env));
+        assert!(is_true("(= 1 1.0)", &mut env));
+        assert!(!is_true("(= (list) nil)", &mut env));
+
+        eval("(def! a (atom 1))", &mut env);
+        assert!(is_true("(= a a)", &mut env));
+        assert!(!is_true("(= a (atom 1))", &mut env));
+
+        eval("(def! l (list 1 2))", &mut env);
+        assert!(is_true("(identical? l l)", &mut env));
+        assert!(!is_true("(identical? l (list 1 2))", &mut env));
+        assert!(!is_true("(identical? 1 1.0)", &mut env));
+    }
+
+    #[test]
+    fn compare_returns_sign() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(compare 1 2.5)", &mut env).to_string(), "-1");
+        assert_eq!(eval("(compare \"b\" \"a\")", &mut env).to_string(), "1");
+        assert_eq!(eval("(compare :a :a)", &mut env).to_string(), "0");
+    }
+
+    #[test]
+    fn numbers_are_equal_by_exact_value() {
+        let mut env = Scope::new();
+
+        assert!(!is_true("(= 9007199254740993 9007199254740992.0)", &mut env));
+        assert!(is_true("(= 9007199254740992 9007199254740992.0)", &mut env));
+        assert!(is_true("(= 1/2 0.5)", &mut env));
+        assert!(!is_true("(= 1/3 0.3333333333333333)", &mut env));
+
+        // NaN is equal to itself as a value, so it can be found in a set, but isn't ordered
+        eval("(def! nan (sqrt -1))", &mut env);
+        assert!(is_true("(= nan nan)", &mut env));
+        assert!(!is_true("(< nan 1)", &mut env));
+        assert!(is_true("(contains? #{nan} nan)", &mut env));
+    }
+
+    #[test]
+    fn hash_agrees_with_equality() {
+        let mut env = Scope::new();
+
+        assert!(is_true("(= (hash 2) (hash 2.0) (hash 4/2))", &mut env));
+        assert!(is_true("(= (hash 0) (hash -0.0))", &mut env));
+        assert!(is_true("(= (hash {:a 1 :b 2}) (hash {:b 2 :a 1}))", &mut env));
+        assert!(is_true("(= (hash (list 1 #{2 3})) (hash (list 1.0 #{3 2})))", &mut env));
+        assert_eq!(eval("(get (assoc {} (list 0 1) :a (list 1 0) :b) (list 1.0 0))", &mut env).to_string(), ":b");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::symbol::Symbol;
+use crate::exec::eval::eval_ast;
+use crate::arg_return;
+
+/// the help text for a binding the way doc prints it, None when name isn't bound
+pub fn doc_string(name: &str, env: &Scope) -> Option<String> {
+    env.get(name)?;
+
+    let doc = env.get_doc(name).unwrap_or_default();
+    let mut lines = vec!["-------------------------".to_string(), name.to_string()];
+
+    if let Some(usage) = doc.usage {
+        lines.push(usage);
+    }
+
+    lines.push(format!("  {}", doc.text.as_deref().unwrap_or("no documentation")));
+
+    Some(lines.join("\n"))
+}
+
+/// (doc name), the name is not evaluated
+pub fn apply_doc(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(doc, 1, list);
+
+    match &list[1] {
+        LispValue::Symbol(a) => match doc_string(a.name(), env) {
+            Some(text) => {
+                println!("{}", text);
+                LispValue::Nil
+            },
+            None => LispValue::Error(format!("symbol not found: {}", a.name()))
+        },
+        _ => LispValue::Error("doc takes a symbol".to_string())
+    }
+}
+
+/// (apropos "str") or (apropos #"regex") lists the bound names that match, sorted
+pub fn apply_apropos(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(apropos, 1, list);
+
+    let names = env.names();
+
+    let matching: Vec<String> = match eval_ast(&list[1], env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        LispValue::String(s) => names.into_iter().filter(|name| name.contains(s.as_str())).collect(),
+        LispValue::Regex(p) => names.into_iter().filter(|name| p.regex().is_match(name)).collect(),
+        _ => return LispValue::Error("apropos takes a string or a regex".to_string())
+    };
+
+    LispValue::List(List::from_vec(matching.into_iter()
+        .map(|name| LispValue::Symbol(Symbol::new(&name)))
+        .collect()))
+}
+
+/// (dir) prints every name visible from the current scope, one per line
+pub fn apply_dir(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(dir, 0, list);
+
+    for name in env.names() {
+        println!("{}", name);
+    }
+
+    LispValue::Nil
+}
+
+#[cfg(test)]
+mod test {
+    use super::doc_string;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn every_builtin_is_documented() {
+        let env = Scope::new();
+
+        for name in env.names() {
+            match env.get_doc(&name) {
+                Some(doc) => assert!(doc.text.is_some(), "{} has no docstring", name),
+                None => panic!("{} has no documentation", name)
+            }
+        }
+    }
+
+    #[test]
+    fn docstrings_on_definitions() {
+        let mut env = Scope::new();
+
+        eval("(def! answer \"the answer\" 42)", &mut env);
+        eval("(defn add2 \"adds two numbers\" ((a) a) ((a b) (+ a b)))", &mut env);
+        eval("(defmacro! unless2 \"unless, again\" (lambda (test body) (if test nil body)))", &mut env);
+
+        assert_eq!(doc_string("answer", &env).unwrap(), "-------------------------\nanswer\n  the answer");
+        assert_eq!(doc_string("add2", &env).unwrap(), "-------------------------\nadd2\n(add2 a) (add2 a b)\n  adds two numbers");
+        assert!(doc_string("unless2", &env).unwrap().ends_with("unless, again"));
+        assert!(doc_string("inc", &env).unwrap().contains("(inc x)"));
+        assert!(doc_string("missing", &env).is_none());
+
+        // redefining without a docstring forgets the old one
+        eval("(def! answer 43)", &mut env);
+        assert!(doc_string("answer", &env).unwrap().ends_with("no documentation"));
+    }
+
+    #[test]
+    fn apropos_lists_matching_names() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(apropos \"re-\")", &mut env).to_string(), "(re-find re-matches re-pattern re-replace re-seq)");
+        assert_eq!(eval("(apropos #\"^when\")", &mut env).to_string(), "(when when-not)");
+    }
+}
@@ -1,8 +1,12 @@
 use crate::types::list::List;
 use crate::exec::eval::{eval_ast};
 use std::fs;
+use std::io::{BufRead, BufReader, Lines};
+use std::cell::RefCell;
+use std::rc::Rc;
 use crate::types::ast::LispValue;
 use crate::types::env::Scope;
+use crate::types::lazy::{LazySeq, Next};
 
 pub fn apply_slurp(list: &List, env: &mut Scope) -> LispValue {
     if list.len() != 2 {
@@ -22,4 +26,38 @@ pub fn apply_slurp(list: &List, env: &mut Scope) -> LispValue {
         }
 
     }
-}
\ No newline at end of file
+}
+
+type FileLines = Rc<RefCell<Lines<BufReader<fs::File>>>>;
+
+// each line is read when its step of the seq is realized, and realized steps are remembered,
+// so the file is read once from start to end however the seq is walked
+fn lines_from(lines: FileLines, scope: &Scope) -> LispValue {
+    LispValue::LazySeq(LazySeq::new(Rc::new(move |scope| {
+        let next = lines.borrow_mut().next();
+
+        Next::Done(match next {
+            None => LispValue::Nil,
+            Some(Err(e)) => LispValue::Error(e.to_string()),
+            Some(Ok(line)) => LispValue::LazySeq(LazySeq::cons(LispValue::String(line), lines_from(lines.clone(), scope)))
+        })
+    }), scope))
+}
+
+/// (line-seq filename) is a lazy seq of the lines of a file, for files too big to slurp
+pub fn apply_line_seq(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() != 2 {
+        return LispValue::Error("line-seq takes only one argument".to_string())
+    }
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(error) => LispValue::Error(error),
+
+        LispValue::String(filename) => match fs::File::open(filename) {
+            Err(e) => LispValue::Error(e.to_string()),
+            Ok(file) => lines_from(Rc::new(RefCell::new(BufReader::new(file).lines())), env)
+        },
+
+        _ => LispValue::Error("line-seq needs a filename!".to_string())
+    }
+}
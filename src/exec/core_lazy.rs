@@ -0,0 +1,391 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::function::Function;
+use crate::types::lazy::{LazySeq, Next, Step};
+use crate::types::printer::pr_str;
+use crate::exec::eval::{eval_ast, eval_args, call_function};
+use crate::exec::core_recursive::{eval_body, is_truthy};
+use crate::exec::core_list::to_seq;
+use crate::types::numeric::{numeric_ordering, to_float};
+use crate::exec::math::add_helper;
+use crate::arg_return;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+// every seq below is built one step at a time: a thunk realizes the next item and hands back a
+// lazy seq for the rest, so nothing is computed before it is asked for and walking a long seq
+// never nests deeper than a single step. a seq made from another asks for its steps with
+// Next::After rather than realizing them itself, so stacking them doesn't nest either.
+
+fn lazy(scope: &Scope, thunk: impl Fn(&mut Scope) -> Next + 'static) -> LispValue {
+    LispValue::LazySeq(LazySeq::new(Rc::new(thunk), scope))
+}
+
+/// realizes the first step of seq and carries on with then
+fn after(seq: LispValue, then: impl FnOnce(Step, &mut Scope) -> Next + 'static) -> Next {
+    Next::After(seq, Box::new(then))
+}
+
+fn cons(first: LispValue, rest: LispValue) -> LispValue {
+    LispValue::LazySeq(LazySeq::cons(first, rest))
+}
+
+fn function_arg(value: LispValue, fname: &str) -> Result<Function, LispValue> {
+    match value {
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        LispValue::Function(f) => Ok(f),
+        other => Err(LispValue::Error(format!("{} needs a function but got {}", fname, pr_str(&other, true))))
+    }
+}
+
+/// (lazy-seq body...) doesn't evaluate the body until the seq is walked, then remembers the result
+pub fn apply_lazy_seq(list: &List, env: &mut Scope) -> LispValue {
+    let body = list.rest();
+
+    lazy(env, move |scope| Next::Done(eval_body(&body, 0, &mut scope.new_scope())))
+}
+
+fn range_from(start: LispValue, end: Option<LispValue>, step: LispValue, scope: &Scope) -> LispValue {
+    lazy(scope, move |scope| {
+        let done = match &end {
+            None => false,
+            Some(end) => match numeric_ordering(&step, &LispValue::Int(0)) {
+                Some(Ordering::Less) => numeric_ordering(&start, end) != Some(Ordering::Greater),
+                _ => numeric_ordering(&start, end) != Some(Ordering::Less)
+            }
+        };
+
+        if done {
+            return Next::Done(LispValue::Nil)
+        }
+
+        Next::Done(match add_helper(start.clone(), step.clone()) {
+            LispValue::Error(e) => LispValue::Error(e),
+            next => cons(start.clone(), range_from(next, end.clone(), step.clone(), scope))
+        })
+    })
+}
+
+/// (range), (range end), (range start end) or (range start end step). without an end it goes on forever
+pub fn apply_range(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    if let Some(arg) = args.iter().find(|arg| to_float(arg).is_none()) {
+        return LispValue::Error(format!("range needs numbers but got {}", pr_str(arg, true)))
+    }
+
+    let mut args = args.into_iter();
+
+    match (args.next(), args.next(), args.next(), args.next()) {
+        (None, _, _, _) => range_from(LispValue::Int(0), None, LispValue::Int(1), env),
+        (Some(end), None, _, _) => range_from(LispValue::Int(0), Some(end), LispValue::Int(1), env),
+        (Some(start), Some(end), None, _) => range_from(start, Some(end), LispValue::Int(1), env),
+        (Some(start), Some(end), Some(step), None) => range_from(start, Some(end), step, env),
+        _ => LispValue::Error("range takes at most 3 args".to_string())
+    }
+}
+
+fn iterate_from(f: Function, x: LispValue, scope: &Scope) -> LispValue {
+    let previous = x.clone();
+
+    cons(x, lazy(scope, move |scope| Next::Done(match call_function(&f, vec![previous.clone()], scope) {
+        LispValue::Error(e) => LispValue::Error(e),
+        next => iterate_from(f.clone(), next, scope)
+    })))
+}
+
+/// (iterate f x) is x, (f x), (f (f x)) and so on
+pub fn apply_iterate(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(iterate, 2, list);
+
+    let f = match function_arg(eval_ast(&list[1], env), "iterate") {
+        Err(e) => return e,
+        Ok(f) => f
+    };
+
+    match eval_ast(&list[2], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        x => iterate_from(f, x, env)
+    }
+}
+
+fn repeat_forever(x: LispValue, scope: &Scope) -> LispValue {
+    lazy(scope, move |scope| Next::Done(cons(x.clone(), repeat_forever(x.clone(), scope))))
+}
+
+fn take_from(n: i64, seq: LispValue, scope: &Scope) -> LispValue {
+    lazy(scope, move |_| {
+        if n <= 0 {
+            return Next::Done(LispValue::Nil)
+        }
+
+        after(seq.clone(), move |step, scope| Next::Done(match step {
+            None => LispValue::Nil,
+            Some((first, rest)) => cons(first, take_from(n - 1, rest, scope))
+        }))
+    })
+}
+
+/// (repeat x) is x forever, (repeat n x) is n of them
+pub fn apply_repeat(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    match args.as_slice() {
+        [x] => repeat_forever(x.clone(), env),
+        [LispValue::Int(n), x] => take_from(*n, repeat_forever(x.clone(), env), env),
+        [_, _] => LispValue::Error("repeat needs an int count".to_string()),
+        _ => LispValue::Error("usage (repeat n? x)".to_string())
+    }
+}
+
+fn cycle_from(current: LispValue, whole: LispValue, scope: &Scope) -> LispValue {
+    fn next_pass(step: Step, whole: LispValue, scope: &mut Scope) -> Next {
+        Next::Done(match step {
+            None => LispValue::Nil,
+            Some((first, rest)) => cons(first, cycle_from(rest, whole, scope))
+        })
+    }
+
+    lazy(scope, move |_| {
+        let whole = whole.clone();
+
+        after(current.clone(), move |step, scope| match step {
+            // once the current pass is used up start over from the whole seq
+            None => after(whole.clone(), move |step, scope| next_pass(step, whole, scope)),
+            step => next_pass(step, whole, scope)
+        })
+    })
+}
+
+/// (cycle coll) repeats the items of coll forever, an empty coll gives an empty seq
+pub fn apply_cycle(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(cycle, 1, list);
+
+    match to_seq(eval_ast(&list[1], env)) {
+        Err(e) => e,
+        Ok(seq) => cycle_from(seq.clone(), seq, env)
+    }
+}
+
+/// steps through the seqs still to go one after another, then calls f on their first items
+fn map_step(f: Function, mut seqs: std::vec::IntoIter<LispValue>, mut firsts: Vec<LispValue>, mut rests: Vec<LispValue>, scope: &mut Scope) -> Next {
+    match seqs.next() {
+        None => Next::Done(match call_function(&f, firsts, scope) {
+            LispValue::Error(e) => LispValue::Error(e),
+            value => cons(value, map_over(f.clone(), rests, scope))
+        }),
+        Some(seq) => after(seq, move |step, scope| match step {
+            // stops as soon as any of the seqs runs out
+            None => Next::Done(LispValue::Nil),
+            Some((first, rest)) => {
+                firsts.push(first);
+                rests.push(rest);
+                map_step(f, seqs, firsts, rests, scope)
+            }
+        })
+    }
+}
+
+fn map_over(f: Function, seqs: Vec<LispValue>, scope: &Scope) -> LispValue {
+    lazy(scope, move |scope| {
+        let n = seqs.len();
+
+        map_step(f.clone(), seqs.clone().into_iter(), Vec::with_capacity(n), Vec::with_capacity(n), scope)
+    })
+}
+
+/// (map f coll & colls) lazily calls f on the first items of every coll, then the second ones and so on
+pub fn apply_map(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() < 3 {
+        return LispValue::Error("usage (map f coll & colls)".to_string())
+    }
+
+    let mut args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args.into_iter()
+    };
+
+    let f = match function_arg(args.next().unwrap(), "map") {
+        Err(e) => return e,
+        Ok(f) => f
+    };
+
+    match args.map(to_seq).collect() {
+        Err(e) => e,
+        Ok(seqs) => map_over(f, seqs, env)
+    }
+}
+
+/// steps through seq until an item passes pred. each skipped item asks for the next step again
+/// instead of recursing, so a long run of them doesn't nest
+fn filter_step(pred: Function, seq: LispValue) -> Next {
+    after(seq, move |step, scope| {
+        let (item, rest) = match step {
+            None => return Next::Done(LispValue::Nil),
+            Some(step) => step
+        };
+
+        match call_function(&pred, vec![item.clone()], scope) {
+            LispValue::Error(e) => Next::Done(LispValue::Error(e)),
+            keep if is_truthy(&keep) => Next::Done(cons(item, filter_over(pred.clone(), rest, scope))),
+            _ => filter_step(pred, rest)
+        }
+    })
+}
+
+fn filter_over(pred: Function, seq: LispValue, scope: &Scope) -> LispValue {
+    lazy(scope, move |_| filter_step(pred.clone(), seq.clone()))
+}
+
+/// (filter pred coll) lazily keeps the items of coll that pred is truthy for
+pub fn apply_filter(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(filter, 2, list);
+
+    let pred = match function_arg(eval_ast(&list[1], env), "filter") {
+        Err(e) => return e,
+        Ok(f) => f
+    };
+
+    match to_seq(eval_ast(&list[2], env)) {
+        Err(e) => e,
+        Ok(seq) => filter_over(pred, seq, env)
+    }
+}
+
+/// (take n coll) is a lazy seq of the first n items of coll
+pub fn apply_take(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(take, 2, list);
+
+    let n = match eval_ast(&list[1], env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        LispValue::Int(n) => n,
+        other => return LispValue::Error(format!("take needs an int count but got {}", pr_str(&other, true)))
+    };
+
+    match to_seq(eval_ast(&list[2], env)) {
+        Err(e) => e,
+        Ok(seq) => take_from(n, seq, env)
+    }
+}
+
+/// (doall seq) realizes every item of seq and returns it, so errors show up right away
+pub fn apply_doall(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(doall, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::LazySeq(s) => match s.to_vec() {
+            Err(e) => LispValue::Error(e),
+            Ok(_) => LispValue::LazySeq(s)
+        },
+        other => other
+    }
+}
+
+pub fn apply_is_realized(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_realized, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        LispValue::LazySeq(s) => LispValue::Boolean(s.is_realized()),
+        other => LispValue::Error(format!("realized? needs a lazy seq but got {}", pr_str(&other, true)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn infinite_seqs() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(take 5 (iterate inc 0))", &mut env).to_string(), "(0 1 2 3 4)");
+        assert_eq!(eval("(range 3)", &mut env).to_string(), "(0 1 2)");
+        assert_eq!(eval("(range 1 10 3)", &mut env).to_string(), "(1 4 7)");
+        assert_eq!(eval("(range 3 0 -1)", &mut env).to_string(), "(3 2 1)");
+        assert_eq!(eval("(take 3 (range))", &mut env).to_string(), "(0 1 2)");
+        assert_eq!(eval("(range 0)", &mut env).to_string(), "()");
+        assert_eq!(eval("(take 5 (cycle (list 1 2)))", &mut env).to_string(), "(1 2 1 2 1)");
+        assert_eq!(eval("(repeat 2 :a)", &mut env).to_string(), "(:a :a)");
+        assert_eq!(eval("(take 2 (repeat \"x\"))", &mut env).to_string(), "(x x)");
+        assert_eq!(eval("(take 3 (filter even? (map (lambda (x) (* x x)) (range))))", &mut env).to_string(), "(0 4 16)");
+        assert_eq!(eval("(map + (list 1 2 3) (range 10 100))", &mut env).to_string(), "(11 13 15)");
+    }
+
+    #[test]
+    fn lazy_seqs_are_realized_once() {
+        let mut env = Scope::new();
+
+        eval("(def! calls (atom 0))", &mut env);
+        eval("(defn nums (n) (lazy-seq (swap! calls inc) (cons n (nums (inc n)))))", &mut env);
+        eval("(def! s (nums 0))", &mut env);
+
+        assert_eq!(eval("(realized? s)", &mut env).to_string(), "false");
+        assert_eq!(eval("@calls", &mut env).to_string(), "0");
+
+        assert_eq!(eval("(doall (take 3 s))", &mut env).to_string(), "(0 1 2)");
+        assert_eq!(eval("(doall (take 3 s))", &mut env).to_string(), "(0 1 2)");
+        assert_eq!(eval("@calls", &mut env).to_string(), "3");
+        assert_eq!(eval("(realized? s)", &mut env).to_string(), "true");
+
+        assert_eq!(eval("(= (take 3 s) (list 0 1 2))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= (hash (range 3)) (hash (list 0 1 2)))", &mut env).to_string(), "true");
+        assert_eq!(eval("(first (rest s))", &mut env).to_string(), "1");
+    }
+
+    #[test]
+    fn long_chains_dont_overflow() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(count (filter (lambda (x) (= x 99999)) (range 100000)))", &mut env).to_string(), "1");
+        assert_eq!(eval("(first (filter (lambda (x) (> x 200000)) (map inc (range))))", &mut env).to_string(), "200001");
+        assert!(matches!(eval("(doall (map (lambda (x) (/ 1 x)) (list 1 0)))", &mut env), LispValue::Error(_)));
+
+        // functions close over the scope they were made in, so a seq built by recursion doesn't nest scopes
+        eval("(defn nums (n) (lazy-seq (cons n (nums (inc n)))))", &mut env);
+        assert_eq!(eval("(count (take 20000 (nums 0)))", &mut env).to_string(), "20000");
+        assert_eq!(eval("(let (adder (let (x 5) (lambda (y) (+ x y)))) (take 2 (map adder (range))))", &mut env).to_string(), "(5 6)");
+    }
+
+    #[test]
+    fn infinite_seqs_print_and_hash() {
+        let mut env = Scope::new();
+
+        // printing stops after LAZY_PRINT_LENGTH items
+        assert_eq!(eval("(re-find #\"998 999 \\.\\.\\.\\)$\" (str (range)))", &mut env).to_string(), "998 999 ...)");
+        assert_eq!(eval("(= (str (range)) (str (range 1001)))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= (str (range 1000)) (str (range 1001)))", &mut env).to_string(), "false");
+        assert_eq!(eval("(re-find #\"^.{11}\" (pr-str (map str (range))))", &mut env).to_string(), "(\"0\" \"1\" \"2");
+
+        // hashing only realizes a prefix, so an infinite seq can be a key
+        assert_eq!(eval("(= (hash (range)) (hash (iterate inc 0)))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= (hash (range 300)) (hash (range 400)))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= (hash (range 3)) (hash (range 4)))", &mut env).to_string(), "false");
+        eval("(def! r (range))", &mut env);
+        assert_eq!(eval("(get (assoc {} r :all) r)", &mut env).to_string(), ":all");
+        assert_eq!(eval("(contains? (conj #{} r) r)", &mut env).to_string(), "true");
+    }
+
+    #[test]
+    fn deeply_nested_seqs_dont_overflow() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(loop (s (range) i 0) (if (< i 500) (recur (map inc s) (inc i)) (first s)))", &mut env).to_string(), "500");
+
+        // every layer asks the one below it for a step, which is realized on a stack rather than by recursing
+        eval("(def! nest (lambda (depth) (loop (s (range 3) n 0) (if (= n depth) s (recur (filter number? (take 5 (map inc s))) (+ n 1))))))", &mut env);
+        assert_eq!(eval("(first (nest 10000))", &mut env).to_string(), "10000");
+        assert_eq!(eval("(nest 10000)", &mut env).to_string(), "(10000 10001 10002)");
+        assert_eq!(eval("(take 4 (cycle (nest 4000)))", &mut env).to_string(), "(4000 4001 4002 4000)");
+        assert!(matches!(eval("(first (map inc (nest 3000) (map (lambda (x) (/ 1 0)) (range))))", &mut env), LispValue::Error(_)));
+    }
+}
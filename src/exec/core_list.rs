@@ -1,42 +1,195 @@
 use crate::types::list::List;
 use crate::types::ast::LispValue;
+use crate::types::lazy::{LazySeq, Step, seq_step};
 use crate::types::env::Scope;
+use crate::types::printer::pr_str;
 use crate::arg_return;
 use crate::exec::eval::eval_ast;
 
+/// (cons x seq) puts x in front of a list or a lazy seq without copying or realizing it
 pub fn apply_cons(list: &List, env: &mut Scope) -> LispValue {
     arg_return!(cons, 2, list);
 
-    if let LispValue::List(l) = eval_ast(&list[2],  env) {
-        let mut r = List::new();
+    let head = eval_ast(&list[1], env);
 
-        r.push(eval_ast(&list[1], env));
-
-        l.items().iter().for_each(|item| {
-            r.push(item.clone())
-        });
-
-        LispValue::List(r)
-    } else {
-        LispValue::Error("second argument to cons needs to be list".to_string())
+    match eval_ast(&list[2],  env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        // the new cell points at l, nothing is copied
+        LispValue::List(l) => LispValue::List(l.cons(head)),
+        LispValue::Nil => LispValue::List(List::new().cons(head)),
+        seq @ LispValue::LazySeq(_) => LispValue::LazySeq(LazySeq::cons(head, seq)),
+        _ => LispValue::Error("second argument to cons needs to be a list or a lazy seq".to_string())
     }
 }
 
 pub fn apply_concat(list: &List, env: &mut Scope) -> LispValue {
 
-    let mut r = List::new();
+    let mut lists = Vec::new();
 
-    for maybe_list in list.items().iter().skip(1) {
+    for maybe_list in list.iter().skip(1) {
 
         if let LispValue::List(l) =  eval_ast(maybe_list, env) {
-            l.items().iter().for_each(|item| {
-                    r.push(item.clone());
-                }
-            )
+            lists.push(l)
         } else {
             return LispValue::Error("every argument to concat must be a list".to_string());
         }
     }
 
-    LispValue::List(r)
-}
\ No newline at end of file
+    // the last list is shared as the tail, only the ones in front of it are copied
+    let tail = lists.pop().unwrap_or_default();
+
+    LispValue::List(lists.iter().rev().fold(tail, |tail, l| {
+        l.to_vec().into_iter().rev().fold(tail, |tail, item| tail.cons(item))
+    }))
+}
+
+/// views a collection as a seq: nil, lists and lazy seqs already are seqs, strings give their
+/// characters, sets their elements and maps their (key value) entries
+pub fn to_seq(value: LispValue) -> Result<LispValue, LispValue> {
+    match value {
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        seq @ LispValue::Nil | seq @ LispValue::LazySeq(_) => Ok(seq),
+        LispValue::List(l) => Ok(LispValue::List(l.with_meta(None))),
+        LispValue::String(s) => Ok(LispValue::List(s.chars().map(LispValue::Char).collect())),
+        LispValue::Set(s) => Ok(LispValue::List(s.iter().cloned().collect())),
+        LispValue::Map(m) => Ok(LispValue::List(m.iter()
+            .map(|(k, v)| LispValue::List(List::from_vec(vec![k.clone(), v.clone()])))
+            .collect())),
+        other => Err(LispValue::Error(format!("cannot make a seq out of {}", pr_str(&other, true))))
+    }
+}
+
+/// the first item and the rest of any collection, realizing only the first step of a lazy seq
+fn first_step(value: LispValue) -> Result<Step, LispValue> {
+    seq_step(&to_seq(value)?).map_err(LispValue::Error)
+}
+
+/// (first coll) is the first item, nil for an empty collection or nil
+pub fn apply_first(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(first, 1, list);
+
+    match first_step(eval_ast(&list[1], env)) {
+        Err(e) => e,
+        Ok(step) => step.map(|(first, _)| first).unwrap_or(LispValue::Nil)
+    }
+}
+
+/// (rest coll) is everything after the first item. it shares the cells of a list instead of copying them
+pub fn apply_rest(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(rest, 1, list);
+
+    match first_step(eval_ast(&list[1], env)) {
+        Err(e) => e,
+        Ok(Some((_, LispValue::Nil))) | Ok(None) => LispValue::List(List::new()),
+        Ok(Some((_, rest))) => rest
+    }
+}
+
+fn count_of(value: LispValue) -> Result<usize, LispValue> {
+    match value {
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        LispValue::Nil => Ok(0),
+        LispValue::List(l) => Ok(l.len()),
+        LispValue::Map(m) => Ok(m.len()),
+        LispValue::Record(r) => Ok(r.fields().len()),
+        LispValue::Set(s) => Ok(s.len()),
+        LispValue::String(s) => Ok(s.chars().count()),
+        // counting realizes the whole seq
+        LispValue::LazySeq(s) => s.iter().try_fold(0, |n, item| item.map(|_| n + 1)).map_err(LispValue::Error),
+        other => Err(LispValue::Error(format!("cannot count {}", pr_str(&other, true))))
+    }
+}
+
+pub fn apply_count(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(count, 1, list);
+
+    match count_of(eval_ast(&list[1], env)) {
+        Err(e) => e,
+        Ok(n) => LispValue::Int(n as i64)
+    }
+}
+
+pub fn apply_is_empty(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(empty, 1, list);
+
+    match first_step(eval_ast(&list[1], env)) {
+        Err(e) => e,
+        Ok(step) => LispValue::Boolean(step.is_none())
+    }
+}
+
+/// (seq coll) views a collection as a seq, see to_seq. empty collections and nil give nil.
+pub fn apply_seq(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(seq, 1, list);
+
+    let seq = match to_seq(eval_ast(&list[1], env)) {
+        Err(e) => return e,
+        Ok(seq) => seq
+    };
+
+    match seq_step(&seq) {
+        Err(e) => LispValue::Error(e),
+        Ok(None) => LispValue::Nil,
+        Ok(Some(_)) => seq
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn cons_and_rest_share_structure() {
+        let mut env = Scope::new();
+
+        eval("(def! l (list 2 3))", &mut env);
+        eval("(def! m (cons 1 l))", &mut env);
+
+        assert_eq!(eval("m", &mut env).to_string(), "(1 2 3)");
+        assert_eq!(eval("l", &mut env).to_string(), "(2 3)");
+        assert_eq!(eval("(identical? (rest m) l)", &mut env).to_string(), "true");
+        assert_eq!(eval("(first m)", &mut env).to_string(), "1");
+        assert_eq!(eval("(count m)", &mut env).to_string(), "3");
+
+        assert_eq!(eval("(first nil)", &mut env).to_string(), "nil");
+        assert_eq!(eval("(rest nil)", &mut env).to_string(), "()");
+        assert_eq!(eval("(rest (list 1))", &mut env).to_string(), "()");
+        assert_eq!(eval("(empty? (list))", &mut env).to_string(), "true");
+        assert_eq!(eval("(count \"abc\")", &mut env).to_string(), "3");
+        assert_eq!(eval("(concat (list 1 2) (list) (list 3))", &mut env).to_string(), "(1 2 3)");
+    }
+
+    #[test]
+    fn long_lists_are_cheap_to_build_and_drop() {
+        let mut env = Scope::new();
+
+        eval("(def! l (loop (n 100000 acc (list)) (if (zero? n) acc (recur (dec n) (cons n acc)))))", &mut env);
+
+        assert_eq!(eval("(count l)", &mut env).to_string(), "100000");
+        assert_eq!(eval("(first l)", &mut env).to_string(), "1");
+        assert_eq!(eval("(count (rest l))", &mut env).to_string(), "99999");
+
+        eval("(def! m (loop (n 1000 m {}) (if (zero? n) m (recur (dec n) (assoc m n (* n n))))))", &mut env);
+        eval("(def! m2 (dissoc m 10))", &mut env);
+
+        assert_eq!(eval("(count m)", &mut env).to_string(), "1000");
+        assert_eq!(eval("(get m 10)", &mut env).to_string(), "100");
+        assert_eq!(eval("(count m2)", &mut env).to_string(), "999");
+        assert_eq!(eval("(get m2 10)", &mut env).to_string(), "nil");
+        assert_eq!(eval("(get m2 11)", &mut env).to_string(), "121");
+    }
+
+    #[test]
+    fn maps_keep_the_first_key() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(assoc {1 :a} 1.0 :b)", &mut env).to_string(), "{1 :b}");
+        assert_eq!(eval("(hash-map 1 :a 1.0 :b)", &mut env).to_string(), "{1 :b}");
+        assert!(matches!(read_string(&Tokenizer::new(), "{1 :a 1.0 :b}".to_string()), LispValue::Error(_)));
+        assert!(matches!(read_string(&Tokenizer::new(), "{:a 1 :a 2}".to_string()), LispValue::Error(_)));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::types::list::List;
+use crate::types::map::Map;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::printer::pr_str;
+use crate::exec::eval::eval_args;
+use crate::arg_return;
+
+/// nil works as the empty map for lookups and assoc, like it does in clojure,
+/// and a record can be read like a map of its fields
+fn to_map(value: &LispValue, fname: &str) -> Result<Map, LispValue> {
+    match value {
+        LispValue::Map(m) => Ok(m.clone()),
+        LispValue::Record(r) => Ok(r.fields().clone()),
+        LispValue::Nil => Ok(Map::new()),
+        _ => Err(LispValue::Error(format!("{} needs a map", fname)))
+    }
+}
+
+pub fn apply_hash_map(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    if args.len() % 2 != 0 {
+        return LispValue::Error("hash-map needs an even number of args".to_string())
+    }
+
+    let pairs = args.chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect();
+
+    LispValue::Map(Map::from_pairs(pairs))
+}
+
+/// (get m key default?)
+pub fn apply_get(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() != 3 && list.len() != 4 {
+        return LispValue::Error("get takes 2 or 3 args".to_string())
+    }
+
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    let default = args.get(2).cloned().unwrap_or(LispValue::Nil);
+
+    match to_map(&args[0], "get") {
+        Err(e) => e,
+        Ok(m) => m.get(&args[1]).cloned().unwrap_or(default)
+    }
+}
+
+/// (assoc m key value key value ...)
+pub fn apply_assoc(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    if args.len() < 3 || args.len() % 2 != 1 {
+        return LispValue::Error("assoc needs a map followed by key value pairs".to_string())
+    }
+
+    // a record stays a record, so it can only take values for its own fields
+    if let LispValue::Record(r) = &args[0] {
+        let mut r = r.clone();
+
+        for pair in args[1..].chunks(2) {
+            r = match r.assoc(pair[0].clone(), pair[1].clone()) {
+                Some(r) => r,
+                None => return LispValue::Error(format!("{} has no field {}", r.name(), pr_str(&pair[0], true)))
+            };
+        }
+
+        return LispValue::Record(r)
+    }
+
+    match to_map(&args[0], "assoc") {
+        Err(e) => e,
+        Ok(mut m) => {
+            for pair in args[1..].chunks(2) {
+                m.insert(pair[0].clone(), pair[1].clone());
+            }
+
+            LispValue::Map(m)
+        }
+    }
+}
+
+/// (dissoc m key ...), taking a field out of a record leaves a plain map
+pub fn apply_dissoc(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    if args.is_empty() {
+        return LispValue::Error("dissoc needs a map".to_string())
+    }
+
+    match to_map(&args[0], "dissoc") {
+        Err(e) => e,
+        Ok(mut m) => {
+            args[1..].iter().for_each(|key| m.remove(key));
+
+            LispValue::Map(m)
+        }
+    }
+}
+
+pub fn apply_keys(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(keys, 1, list);
+
+    match eval_args(list, env).and_then(|args| to_map(&args[0], "keys")) {
+        Err(e) => e,
+        Ok(m) => LispValue::List(List::from_vec(m.keys().cloned().collect()))
+    }
+}
+
+pub fn apply_vals(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(vals, 1, list);
+
+    match eval_args(list, env).and_then(|args| to_map(&args[0], "vals")) {
+        Err(e) => e,
+        Ok(m) => LispValue::List(List::from_vec(m.values().cloned().collect()))
+    }
+}
+
+pub fn apply_is_map(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_map, 1, list);
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Boolean(matches!(args[0], LispValue::Map(_)))
+    }
+}
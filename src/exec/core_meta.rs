@@ -1,8 +1,10 @@
 use crate::types::list::List;
 use crate::types::ast::LispValue;
-use crate::types::env::Scope;
+use crate::types::env::{Scope, Doc};
+use crate::types::map::Map;
+use crate::types::printer::pr_str;
+use crate::exec::eval::{eval_ast, eval_args};
 use crate::arg_return;
-use crate::exec::eval::eval_ast;
 
 pub fn apply_quote(list: &List, _env: &mut Scope) -> LispValue {
     if list.len() > 2 {
@@ -12,17 +14,26 @@ pub fn apply_quote(list: &List, _env: &mut Scope) -> LispValue {
     }
 }
 
+/// (macro! name "docstring"? f), also available as defmacro!
 pub fn apply_macro(list: &List, env: &mut Scope) -> LispValue {
-    arg_return!(defmacro, 2, list);
+    let (doc, function_form) = match list.len() {
+        3 => (None, &list[2]),
+        4 => match &list[2] {
+            LispValue::String(doc) => (Some(doc.clone()), &list[3]),
+            _ => return LispValue::Error("the docstring of macro! must be a string".to_string())
+        },
+        _ => return LispValue::Error("usage (macro! name docstring? f)".to_string())
+    };
 
     match &list[1] {
-        LispValue::Unit(a) => {
+        LispValue::Symbol(a) => {
             // need to clone to insert into map
-            let key = a.token().get_text().clone();
-            let value = eval_ast(&list[2], env);
+            let key = a.name().to_string();
+            let value = eval_ast(function_form, env);
 
-            if let LispValue::Function(f) = value {
-                env.set(key, LispValue::Macro(f.clone()));
+            if let LispValue::Function(mut f) = value {
+                f.set_name(key.clone());
+                env.define(key, LispValue::Macro(f.clone()), doc.map(|text| Doc { usage: None, text: Some(text) }));
 
                 LispValue::Macro(f)
             } else {
@@ -34,3 +45,82 @@ pub fn apply_macro(list: &List, env: &mut Scope) -> LispValue {
     }
 }
 
+/// the metadata attached to a value, only functions, macros and collections can carry any
+pub fn meta_of(value: &LispValue) -> Option<&Map> {
+    match value {
+        LispValue::List(l) => l.meta(),
+        LispValue::Map(m) => m.meta(),
+        LispValue::Set(s) => s.meta(),
+        LispValue::Function(f) | LispValue::Macro(f) => f.meta(),
+        _ => None
+    }
+}
+
+/// (with-meta value meta) returns a copy of value with the map meta attached, nil removes it
+pub fn apply_with_meta(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Ok(args) => args,
+        Err(e) => return e
+    };
+
+    let meta = match args.as_slice() {
+        [_, LispValue::Map(m)] => Some(m.clone()),
+        [_, LispValue::Nil] => None,
+        [_, _] => return LispValue::Error("metadata must be a map".to_string()),
+        _ => return LispValue::Error("usage (with-meta value meta)".to_string())
+    };
+
+    match &args[0] {
+        LispValue::List(l) => LispValue::List(l.with_meta(meta)),
+        LispValue::Map(m) => LispValue::Map(m.with_meta(meta)),
+        LispValue::Set(s) => LispValue::Set(s.with_meta(meta)),
+        LispValue::Function(f) => LispValue::Function(f.with_meta(meta)),
+        LispValue::Macro(f) => LispValue::Macro(f.with_meta(meta)),
+        other => LispValue::Error(format!("cannot attach metadata to {}", pr_str(other, true)))
+    }
+}
+
+/// (meta value) is the metadata map of value, or nil when it has none
+pub fn apply_meta(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(meta, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        value => meta_of(&value).cloned().map(LispValue::Map).unwrap_or(LispValue::Nil)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::exec::core_doc::doc_string;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn with_meta_and_meta() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(meta (with-meta (list 1 2) {:line 3}))", &mut env).to_string(), "{:line 3}");
+        assert_eq!(eval("(= (with-meta {:a 1} {:b 2}) {:a 1})", &mut env).to_string(), "true");
+        assert_eq!(eval("(meta (with-meta (with-meta inc {:a 1}) nil))", &mut env).to_string(), "nil");
+        assert_eq!(eval("((with-meta inc {:a 1}) 1)", &mut env).to_string(), "2");
+        assert!(matches!(eval("(with-meta 1 {:a 1})", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(with-meta (list) 1)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn meta_reader_macro() {
+        let mut env = Scope::new();
+
+        assert_eq!(read_string(&Tokenizer::new(), "^{:doc \"x\"} (f)".to_string()).to_string(), "(with-meta (f) {:doc x})");
+        assert_eq!(eval("(meta ^:private '(1))", &mut env).to_string(), "{:private true}");
+        assert_eq!(eval("(meta ^Int '(1))", &mut env).to_string(), "{:tag Int}");
+
+        eval("(def! twice ^{:doc \"doubles x\"} (lambda (x) (* 2 x)))", &mut env);
+        assert_eq!(eval("(twice 4)", &mut env).to_string(), "8");
+        assert!(doc_string("twice", &env).unwrap().ends_with("doubles x"));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::types::list::List;
+use crate::types::map::Map;
+use crate::types::ast::LispValue;
+use crate::types::env::{Scope, Doc};
+use crate::types::function::Function;
+use crate::types::printer::pr_str;
+use crate::exec::eval::{eval_ast, eval_args, call_function};
+use crate::exec::core_recursive::create_func;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn default_key() -> LispValue {
+    LispValue::Keyword("default".to_string())
+}
+
+/// (defmulti name docstring? dispatch-fn) defines name as a function that calls dispatch-fn on its
+/// args and hands them on to the method defmethod registered for the result, or to the :default method
+pub fn apply_defmulti(list: &List, env: &mut Scope) -> LispValue {
+    let name = match list.get(1) {
+        Some(LispValue::Symbol(a)) => a.name().to_string(),
+        _ => return LispValue::Error("usage (defmulti name docstring? dispatch-fn)".to_string())
+    };
+
+    let (doc, dispatch_form) = match (list.len(), list.get(2)) {
+        (3, _) => (None, &list[2]),
+        (4, Some(LispValue::String(doc))) => (Some(doc.clone()), &list[3]),
+        _ => return LispValue::Error("usage (defmulti name docstring? dispatch-fn)".to_string())
+    };
+
+    let dispatch = match eval_ast(dispatch_form, env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        LispValue::Function(f) => f,
+        other => return LispValue::Error(format!("the dispatch of a multimethod must be a function but got {}", pr_str(&other, true)))
+    };
+
+    // the methods by dispatch value, the multimethod carries the table so defmethod can reach it
+    let methods = Rc::new(RefCell::new(Map::new()));
+    let table = methods.clone();
+    let multi_name = name.clone();
+
+    let mut function = Function::new(Rc::new(move |args: &List, env: &mut Scope| {
+        let values = match eval_args(args, env) {
+            Err(e) => return e,
+            Ok(values) => values
+        };
+
+        let dispatch_value = match call_function(&dispatch, values.clone(), env) {
+            LispValue::Error(e) => return LispValue::Error(e),
+            value => value
+        };
+
+        let method = {
+            let table = table.borrow();
+            table.get(&dispatch_value).or_else(|| table.get(&default_key())).cloned()
+        };
+
+        match method {
+            Some(LispValue::Function(method)) => call_function(&method, values, env),
+            _ => LispValue::Error(format!("no method in multimethod {} for dispatch value {}", multi_name, pr_str(&dispatch_value, true)))
+        }
+    }));
+    function.set_name(name.clone());
+    function.set_methods(methods);
+
+    let value = LispValue::Function(function);
+
+    env.define(name.clone(), value.clone(), Some(Doc { usage: Some(format!("({} & args)", name)), text: doc }));
+
+    value
+}
+
+/// (defmethod name dispatch-value (params) body...) adds the method of a multimethod for a
+/// dispatch value, replacing any earlier one. :default catches values no other method takes.
+pub fn apply_defmethod(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() < 4 {
+        return LispValue::Error("usage (defmethod name dispatch-value (args list) body...)".to_string())
+    }
+
+    let name = match &list[1] {
+        LispValue::Symbol(a) => a.name().to_string(),
+        _ => return LispValue::Error("usage (defmethod name dispatch-value (args list) body...)".to_string())
+    };
+
+    let not_multi = || LispValue::Error(format!("{} is not a multimethod", name));
+
+    // evaluating the name resolves it like any other, so ns/name and aliases reach multimethods elsewhere
+    let multi = match eval_ast(&list[1], env) {
+        LispValue::Function(f) => f,
+        _ => return not_multi()
+    };
+
+    let methods = match multi.methods() {
+        Some(methods) => methods.clone(),
+        None => return not_multi()
+    };
+
+    let dispatch_value = match eval_ast(&list[2], env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        value => value
+    };
+
+    // everything after the dispatch value reads like the arguments of lambda
+    let mut method = match create_func(&list.skip(2), env) {
+        LispValue::Function(f) => f,
+        other => return other
+    };
+    method.set_name(name.clone());
+
+    methods.borrow_mut().insert(dispatch_value, LispValue::Function(method));
+
+    LispValue::Function(multi)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn methods_are_picked_by_dispatch_value() {
+        let mut env = Scope::new();
+
+        eval("(defrecord Circle (r))", &mut env);
+        eval("(defrecord Rect (w h))", &mut env);
+        eval("(defmulti area type)", &mut env);
+        eval("(defmethod area :Circle (c) (* 3 (Circle-r c) (Circle-r c)))", &mut env);
+        eval("(defmethod area :Rect ({:keys (w h)}) (* w h))", &mut env);
+
+        assert_eq!(eval("(area (->Circle 2))", &mut env).to_string(), "12");
+        assert_eq!(eval("(area (->Rect 2 3))", &mut env).to_string(), "6");
+        assert!(matches!(eval("(area 5)", &mut env), LispValue::Error(_)));
+
+        eval("(defmethod area :default (x) 0)", &mut env);
+        eval("(defmethod area :Rect (r) -1)", &mut env);
+        assert_eq!(eval("(area 5)", &mut env).to_string(), "0");
+        assert_eq!(eval("(area (->Rect 2 3))", &mut env).to_string(), "-1");
+    }
+
+    #[test]
+    fn dispatch_can_be_any_function() {
+        let mut env = Scope::new();
+
+        eval("(defmulti describe (lambda (x n) (if (> n 1) :many :one)))", &mut env);
+        eval("(defmethod describe :one (x n) (str \"a \" x))", &mut env);
+        eval("(defmethod describe :many (x n) (str n \" \" x \"s\"))", &mut env);
+
+        assert_eq!(eval("(describe \"cat\" 1)", &mut env).to_string(), "a cat");
+        assert_eq!(eval("(describe \"cat\" 3)", &mut env).to_string(), "3 cats");
+        assert!(matches!(eval("(defmethod nothing :x (a) a)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(defmulti broken 1)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn methods_can_be_added_from_another_namespace() {
+        let mut env = Scope::new();
+
+        eval("(ns shapes)", &mut env);
+        eval("(defmulti area type)", &mut env);
+        eval("(ns user)", &mut env);
+        eval("(alias s shapes)", &mut env);
+        eval("(defrecord Sq (side))", &mut env);
+        eval("(defrecord Tri (b h))", &mut env);
+        eval("(defmethod shapes/area :Sq (q) (* (Sq-side q) (Sq-side q)))", &mut env);
+        eval("(defmethod s/area :Tri (t) (/ (* (Tri-b t) (Tri-h t)) 2))", &mut env);
+
+        assert_eq!(eval("(s/area (->Sq 3))", &mut env).to_string(), "9");
+        assert_eq!(eval("(shapes/area (->Tri 4 3))", &mut env).to_string(), "6");
+
+        // the table belongs to the multimethod, nothing else is bound for it
+        assert_eq!(eval("(apropos \"methods\")", &mut env).to_string(), "()");
+        assert!(matches!(eval("(defmethod inc :x (a) a)", &mut env), LispValue::Error(_)));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::printer::pr_str;
+use crate::exec::core_recursive::eval_body;
+use crate::exec::core_utils::read_string;
+use crate::reader::tokenizer::Tokenizer;
+use std::fs;
+use std::path::PathBuf;
+
+pub const LOAD_PATH: &str = "*load-path*";
+
+fn symbol_name(value: &LispValue) -> Result<String, String> {
+    match value {
+        LispValue::Symbol(a) => Ok(a.name().to_string()),
+        other => Err(format!("expected the name of a namespace but got {}", pr_str(other, true)))
+    }
+}
+
+fn symbol_names(value: &LispValue) -> Result<Vec<String>, String> {
+    match value {
+        LispValue::List(names) => names.iter().map(symbol_name).collect(),
+        other => Err(format!("expected a list of names but got {}", pr_str(other, true)))
+    }
+}
+
+// the namespace a.b-c lives in a/b-c.lisp under one of the directories on the load path
+fn find_file(name: &str, env: &Scope) -> Result<PathBuf, String> {
+    let file = format!("{}.lisp", name.replace('.', "/"));
+
+    // the load path is set in user, whichever namespace is doing the require
+    let dirs = match env.namespace("user").and_then(|user| user.get(LOAD_PATH)) {
+        Some(LispValue::List(dirs)) => dirs,
+        _ => return Err(format!("{} must be a list of directories", LOAD_PATH))
+    };
+
+    for dir in &dirs {
+        if let LispValue::String(dir) = dir {
+            let path = PathBuf::from(dir).join(&file);
+
+            if path.is_file() {
+                return Ok(path)
+            }
+        }
+    }
+
+    Err(format!("could not find {} in any directory of {}", file, LOAD_PATH))
+}
+
+/// reads and evaluates the file of a namespace in that namespace, a child of the builtins, so it
+/// neither sees nor changes the definitions of whoever required it. the file has to start with
+/// (ns name) for the name that was required, which is checked before anything is evaluated.
+fn load_namespace(name: &str, env: &Scope) -> Result<Scope, String> {
+    let path = find_file(name, env)?;
+    let contents = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+    let forms = match read_string(&Tokenizer::new(), format!("(do {}\nnil)", contents)) {
+        LispValue::Error(e) => return Err(format!("could not read {}: {}", path.display(), e)),
+        forms => forms
+    };
+
+    let forms = match forms {
+        LispValue::List(forms) => forms,
+        other => return Err(format!("could not read {}: got {}", path.display(), pr_str(&other, true)))
+    };
+
+    let declared = match forms.get(1) {
+        Some(LispValue::List(ns)) if ns.first_symbol() == Some("ns") => ns.get(1).map(symbol_name),
+        _ => None
+    };
+
+    match declared {
+        Some(Ok(declared)) if declared == name => (),
+        Some(Ok(declared)) => return Err(format!("{} declares the namespace {}, not {}", path.display(), declared, name)),
+        _ => return Err(format!("{} has to start with (ns {})", path.display(), name))
+    }
+
+    let loaded_before = env.namespace(name).is_some();
+    let mut namespace = env.create_namespace(name);
+
+    // unlike do, this stops at the first form that fails
+    if let LispValue::Error(e) = eval_body(&forms, 1, &mut namespace) {
+        if !loaded_before {
+            env.remove_namespace(name);
+        }
+
+        return Err(format!("could not load {}: {}", path.display(), e))
+    }
+
+    Ok(namespace)
+}
+
+/// binds the names of the namespace from in the namespace of env, all of them unless only names some
+fn refer_names(from: &Scope, only: Option<Vec<String>>, exclude: &[String], env: &Scope) -> Result<(), String> {
+    let available = from.own_names();
+
+    let names = match only {
+        None => available,
+        Some(names) => match names.iter().find(|name| !available.contains(name)) {
+            Some(missing) => return Err(format!("{} is not defined in {}", missing, from.namespace_name())),
+            None => names
+        }
+    };
+
+    let mut target = env.namespace_scope();
+
+    for name in names.into_iter().filter(|name| !exclude.contains(name)) {
+        if let Some(value) = from.get(&name) {
+            let doc = from.get_doc(&name);
+            target.define(name, value, doc);
+        }
+    }
+
+    Ok(())
+}
+
+/// a spec is the name of a namespace, or a list of it followed by :as alias and :refer (names) or :refer :all
+fn require_spec(spec: &LispValue, reload: bool, env: &Scope) -> Result<(), String> {
+    let (name, options) = match spec {
+        LispValue::List(spec) if !spec.is_empty() => (symbol_name(&spec[0])?, spec.rest().to_vec()),
+        spec => (symbol_name(spec)?, Vec::new())
+    };
+
+    let namespace = match env.namespace(&name) {
+        Some(namespace) if !reload => namespace,
+        _ => load_namespace(&name, env)?
+    };
+
+    for option in options.chunks(2) {
+        match option {
+            [LispValue::Keyword(k), alias] if k == "as" => env.alias(&symbol_name(alias)?, &name),
+            [LispValue::Keyword(k), LispValue::Keyword(all)] if k == "refer" && all == "all" => {
+                refer_names(&namespace, None, &[], env)?
+            },
+            [LispValue::Keyword(k), names] if k == "refer" => refer_names(&namespace, Some(symbol_names(names)?), &[], env)?,
+            _ => return Err(format!("usage (require (name :as alias :refer (names))) but got {}", pr_str(spec, true)))
+        }
+    }
+
+    Ok(())
+}
+
+fn require_all(specs: &List, env: &Scope) -> LispValue {
+    let reload = specs.iter().any(|spec| matches!(spec, LispValue::Keyword(k) if k == "reload"));
+
+    for spec in specs.iter().filter(|spec| !matches!(spec, LispValue::Keyword(_))) {
+        if let Err(e) = require_spec(spec, reload, env) {
+            return LispValue::Error(e)
+        }
+    }
+
+    LispValue::Nil
+}
+
+/// (ns name (:require spec...)) makes name the current namespace, creating it when it's new, so
+/// what is defined after it lands in name. other namespaces reach those definitions as name/x.
+pub fn apply_ns(list: &List, env: &mut Scope) -> LispValue {
+    let name = match list.get(1) {
+        Some(LispValue::Symbol(a)) => a.name().to_string(),
+        _ => return LispValue::Error("usage (ns name (:require spec...))".to_string())
+    };
+
+    *env = env.create_namespace(&name);
+
+    for clause in list.iter().skip(2) {
+        let result = match clause {
+            LispValue::List(clause) if matches!(clause.first(), Some(LispValue::Keyword(k)) if k == "require") => {
+                require_all(&clause.rest(), env)
+            },
+            other => LispValue::Error(format!("usage (ns name (:require spec...)) but got {}", pr_str(other, true)))
+        };
+
+        if let LispValue::Error(e) = result {
+            return LispValue::Error(e)
+        }
+    }
+
+    LispValue::Nil
+}
+
+/// (require spec... :reload?) loads the file of each namespace from the load path unless it was
+/// loaded before, or :reload is given. a spec is a name or (name :as alias :refer (names)).
+pub fn apply_require(list: &List, env: &mut Scope) -> LispValue {
+    require_all(&list.rest(), env)
+}
+
+/// (refer name :only (names)? :exclude (names)?) binds the definitions of the namespace name in
+/// the current namespace, so they can be used without the prefix
+pub fn apply_refer(list: &List, env: &mut Scope) -> LispValue {
+    let usage = || LispValue::Error("usage (refer name :only (names)? :exclude (names)?)".to_string());
+
+    let namespace = match list.get(1).map(symbol_name) {
+        Some(Ok(name)) => match env.namespace(&name) {
+            Some(namespace) => namespace,
+            None => return LispValue::Error(format!("no namespace {}, require it first", name))
+        },
+        Some(Err(e)) => return LispValue::Error(e),
+        None => return usage()
+    };
+
+    let (mut only, mut exclude) = (None, Vec::new());
+
+    for option in list.skip(2).to_vec().chunks(2) {
+        let names = match option {
+            [LispValue::Keyword(_), names] => match symbol_names(names) {
+                Ok(names) => names,
+                Err(e) => return LispValue::Error(e)
+            },
+            _ => return usage()
+        };
+
+        match &option[0] {
+            LispValue::Keyword(k) if k == "only" => only = Some(names),
+            LispValue::Keyword(k) if k == "exclude" => exclude = names,
+            _ => return usage()
+        }
+    }
+
+    match refer_names(&namespace, only, &exclude, env) {
+        Err(e) => LispValue::Error(e),
+        Ok(()) => LispValue::Nil
+    }
+}
+
+/// (alias a name) lets a/x stand for name/x in the current namespace
+pub fn apply_alias(list: &List, env: &mut Scope) -> LispValue {
+    let (alias, name) = match (list.len(), list.get(1).map(symbol_name), list.get(2).map(symbol_name)) {
+        (3, Some(Ok(alias)), Some(Ok(name))) => (alias, name),
+        _ => return LispValue::Error("usage (alias a name)".to_string())
+    };
+
+    if env.namespace(&name).is_none() {
+        return LispValue::Error(format!("no namespace {}, require it first", name))
+    }
+
+    env.alias(&alias, &name);
+    LispValue::Nil
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+    use std::fs;
+
+    #[test]
+    fn namespaces_keep_definitions_apart() {
+        let mut env = Scope::new();
+
+        eval("(ns a)", &mut env);
+        eval("(defn helper () :a)", &mut env);
+        eval("(defn run () (helper))", &mut env);
+        eval("(ns b)", &mut env);
+        eval("(defn helper () :b)", &mut env);
+        eval("(ns user)", &mut env);
+
+        assert_eq!(eval("(a/helper)", &mut env).to_string(), ":a");
+        assert_eq!(eval("(b/helper)", &mut env).to_string(), ":b");
+        assert_eq!(eval("(a/run)", &mut env).to_string(), ":a");
+        assert!(matches!(eval("(helper)", &mut env), LispValue::Error(_)));
+
+        eval("(alias x a)", &mut env);
+        eval("(refer b :only (helper))", &mut env);
+        assert_eq!(eval("(x/helper)", &mut env).to_string(), ":a");
+        assert_eq!(eval("(helper)", &mut env).to_string(), ":b");
+        assert!(matches!(eval("(refer nowhere)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn require_loads_a_file_once() {
+        let dir = std::env::temp_dir().join(format!("lisp-require-{}", std::process::id()));
+        fs::create_dir_all(dir.join("text")).unwrap();
+        fs::write(dir.join("text").join("util.lisp"),
+                  "(ns text.util)\n(swap! user/loads inc)\n(defn helper (s) (str s \"!\"))\n(defn shout (s) (helper s))").unwrap();
+
+        let mut env = Scope::new();
+        eval(&format!("(def! *load-path* (list \"nowhere\" {:?}))", dir.to_str().unwrap()), &mut env);
+        eval("(defn helper (s) s)", &mut env);
+        eval("(def! loads (atom 0))", &mut env);
+
+        assert_eq!(eval("(require (text.util :as t :refer (shout)))", &mut env).to_string(), "nil");
+        assert_eq!(eval("(t/shout \"hi\")", &mut env).to_string(), "hi!");
+        assert_eq!(eval("(shout \"hey\")", &mut env).to_string(), "hey!");
+        assert_eq!(eval("(helper \"x\")", &mut env).to_string(), "x");
+        assert_eq!(eval("(text.util/helper \"y\")", &mut env).to_string(), "y!");
+
+        eval("(require text.util)", &mut env);
+        assert_eq!(eval("@loads", &mut env).to_string(), "1");
+        eval("(require text.util :reload)", &mut env);
+        assert_eq!(eval("@loads", &mut env).to_string(), "2");
+
+        assert!(matches!(eval("(require missing.lib)", &mut env), LispValue::Error(_)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn required_files_have_to_declare_their_namespace() {
+        let dir = std::env::temp_dir().join(format!("lisp-require-ns-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("wrong.lisp"), "(ns right)\n(def! x 1)").unwrap();
+        fs::write(dir.join("first.lisp"), "(def! leaked 1)\n(ns first)").unwrap();
+        fs::write(dir.join("peeks.lisp"), "(ns peeks)\n(def! seen secret)").unwrap();
+
+        let mut env = Scope::new();
+        eval(&format!("(def! *load-path* (list {:?}))", dir.to_str().unwrap()), &mut env);
+        eval("(def! secret 42)", &mut env);
+
+        match eval("(require wrong)", &mut env) {
+            LispValue::Error(e) => assert!(e.ends_with("declares the namespace right, not wrong"), "{}", e),
+            other => panic!("expected an error, got {}", other)
+        }
+        assert!(matches!(eval("right/x", &mut env), LispValue::Error(_)));
+
+        assert!(matches!(eval("(require first)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("leaked", &mut env), LispValue::Error(_)));
+
+        // the file is evaluated in its own namespace, which doesn't see user
+        assert!(matches!(eval("(require peeks)", &mut env), LispValue::Error(_)));
+        assert_eq!(env.namespace_name(), "user");
+        // and a namespace that failed to load isn't left behind half defined
+        assert!(env.namespace("peeks").is_none());
+        assert!(matches!(eval("(require peeks)", &mut env), LispValue::Error(_)));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn namespaces_only_share_the_builtins() {
+        let mut env = Scope::new();
+
+        eval("(def! secret 42)", &mut env);
+        eval("(ns shapes.core)", &mut env);
+        eval("(defn uses-secret () secret)", &mut env);
+        eval("(defn uses-builtin () (inc 1))", &mut env);
+        eval("(ns user)", &mut env);
+
+        assert!(matches!(eval("(shapes.core/uses-secret)", &mut env), LispValue::Error(_)));
+        assert_eq!(eval("(shapes.core/uses-builtin)", &mut env).to_string(), "2");
+        assert_eq!(eval("user/secret", &mut env).to_string(), "42");
+        assert_eq!(eval("(eval 'secret)", &mut env).to_string(), "42");
+    }
+}
@@ -0,0 +1,176 @@
+use crate::types::list::List;
+use crate::types::map::Map;
+use crate::types::record::Record;
+use crate::types::ast::{LispValue, Lambda};
+use crate::types::env::{Scope, Doc};
+use crate::types::function::Function;
+use crate::types::symbol::Symbol;
+use crate::types::printer::pr_str;
+use crate::exec::eval::eval_args;
+use crate::exec::core_type::KIND_NAMES;
+use crate::arg_return;
+use std::rc::Rc;
+
+fn define_function(env: &mut Scope, name: String, lambda: Lambda, usage: String, text: String) {
+    let mut function = Function::new(lambda);
+    function.set_name(name.clone());
+
+    env.define(name, LispValue::Function(function), Some(Doc { usage: Some(usage), text: Some(text) }));
+}
+
+fn one_arg(args: &List, env: &mut Scope, fname: &str) -> Result<LispValue, LispValue> {
+    if args.len() != 2 {
+        return Err(LispValue::Error(format!("{} takes 1 arg", fname)))
+    }
+
+    eval_args(args, env).map(|mut values| values.remove(0))
+}
+
+/// (defrecord Name docstring? (field ...)) defines a record type: ->Name builds one from the
+/// fields in order, map->Name from a map of them, Name? tells records of the type apart and
+/// Name-field reads a field. records print as #Name{:field value ...}.
+pub fn apply_defrecord(list: &List, env: &mut Scope) -> LispValue {
+    let usage = LispValue::Error("usage (defrecord Name docstring? (field ...))".to_string());
+
+    let name = match list.get(1) {
+        Some(LispValue::Symbol(a)) => a.name().to_string(),
+        _ => return usage
+    };
+
+    if KIND_NAMES.contains(&name.as_str()) {
+        return LispValue::Error(format!("{} is the type of built-in values, a record type needs another name", name))
+    }
+
+    let (doc, fields) = match (list.len(), list.get(2), list.get(3)) {
+        (3, Some(LispValue::List(fields)), _) => (None, fields),
+        (4, Some(LispValue::String(doc)), Some(LispValue::List(fields))) => (Some(doc.clone()), fields),
+        _ => return usage
+    };
+
+    let mut names: Vec<String> = Vec::new();
+
+    for field in fields {
+        match field {
+            LispValue::Symbol(a) if names.iter().any(|n| n == a.name()) => {
+                return LispValue::Error(format!("{} has more than one field called {}", name, a.name()))
+            },
+            LispValue::Symbol(a) => names.push(a.name().to_string()),
+            other => return LispValue::Error(format!("record fields must be symbols but got {}", pr_str(other, true)))
+        }
+    }
+
+    let keys: Rc<[LispValue]> = names.iter().map(|n| LispValue::Keyword(n.clone())).collect();
+    let description = match doc {
+        Some(doc) => format!("creates a {} record. {}", name, doc),
+        None => format!("creates a {} record", name)
+    };
+
+    let (type_name, field_keys) = (name.clone(), keys.clone());
+    define_function(env, format!("->{}", name), Rc::new(move |args: &List, env: &mut Scope| {
+        let values = match eval_args(args, env) {
+            Err(e) => return e,
+            Ok(values) => values
+        };
+
+        if values.len() != field_keys.len() {
+            return LispValue::Error(format!("->{} takes {} args", type_name, field_keys.len()))
+        }
+
+        LispValue::Record(Record::new(&type_name, Map::from_pairs(field_keys.iter().cloned().zip(values).collect())))
+    }), format!("(->{} {})", name, names.join(" ")), description.clone());
+
+    // fields missing from the map are nil, keys that aren't fields are an error
+    let (type_name, field_keys) = (name.clone(), keys.clone());
+    define_function(env, format!("map->{}", name), Rc::new(move |args: &List, env: &mut Scope| {
+        let m = match one_arg(args, env, &format!("map->{}", type_name)) {
+            Err(e) => return e,
+            Ok(LispValue::Map(m)) => m,
+            Ok(other) => return LispValue::Error(format!("map->{} needs a map but got {}", type_name, pr_str(&other, true)))
+        };
+
+        if let Some(key) = m.keys().find(|key| !field_keys.contains(key)) {
+            return LispValue::Error(format!("{} has no field {}", type_name, pr_str(key, true)))
+        }
+
+        let fields = field_keys.iter().map(|key| (key.clone(), m.get(key).cloned().unwrap_or(LispValue::Nil))).collect();
+
+        LispValue::Record(Record::new(&type_name, Map::from_pairs(fields)))
+    }), format!("(map->{} m)", name), description);
+
+    let type_name = name.clone();
+    define_function(env, format!("{}?", name), Rc::new(move |args: &List, env: &mut Scope| {
+        match one_arg(args, env, &format!("{}?", type_name)) {
+            Err(e) => e,
+            Ok(value) => LispValue::Boolean(matches!(value, LispValue::Record(r) if r.name() == type_name))
+        }
+    }), format!("({}? x)", name), format!("true when x is a {} record", name));
+
+    for (field, key) in names.iter().zip(keys.iter()) {
+        let (type_name, key) = (name.clone(), key.clone());
+        let accessor = format!("{}-{}", name, field);
+        let accessor_name = accessor.clone();
+
+        define_function(env, accessor.clone(), Rc::new(move |args: &List, env: &mut Scope| {
+            match one_arg(args, env, &accessor_name) {
+                Err(e) => e,
+                Ok(LispValue::Record(r)) if r.name() == type_name => r.get(&key).cloned().unwrap_or(LispValue::Nil),
+                Ok(other) => LispValue::Error(format!("{} needs a {} record but got {}", accessor_name, type_name, pr_str(&other, true)))
+            }
+        }), format!("({} record)", accessor), format!("the {} field of a {} record", field, name));
+    }
+
+    LispValue::Symbol(Symbol::new(&name))
+}
+
+pub fn apply_is_record(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_record, 1, list);
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Boolean(matches!(args[0], LispValue::Record(_)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn records_have_constructors_accessors_and_a_predicate() {
+        let mut env = Scope::new();
+
+        eval("(defrecord Point (x y))", &mut env);
+
+        assert_eq!(eval("(->Point 1 2)", &mut env).to_string(), "#Point{:x 1 :y 2}");
+
+        eval("(def! p (map->Point {:y 2}))", &mut env);
+        assert_eq!(eval("p", &mut env).to_string(), "#Point{:x nil :y 2}");
+        assert_eq!(eval("(Point-x p)", &mut env).to_string(), "nil");
+        assert_eq!(eval("(Point-y p)", &mut env).to_string(), "2");
+        assert_eq!(eval("(get p :y)", &mut env).to_string(), "2");
+        assert_eq!(eval("(Point? p)", &mut env).to_string(), "true");
+        assert_eq!(eval("(record? p)", &mut env).to_string(), "true");
+        assert_eq!(eval("(Point? {:x nil :y 2})", &mut env).to_string(), "false");
+
+        assert_eq!(eval("(assoc (->Point 1 2) :y 3)", &mut env).to_string(), "#Point{:x 1 :y 3}");
+        assert!(matches!(eval("(assoc (->Point 1 2) :z 3)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(->Point 1)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(defrecord Bad (x x))", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn records_compare_by_type_and_fields() {
+        let mut env = Scope::new();
+
+        eval("(defrecord Point (x y))", &mut env);
+        eval("(defrecord Size (x y))", &mut env);
+
+        assert_eq!(eval("(= (->Point 1 2) (->Point 1 2))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= (->Point 1 2) (->Size 1 2))", &mut env).to_string(), "false");
+        assert_eq!(eval("(= (->Point 1 2) {:x 1 :y 2})", &mut env).to_string(), "false");
+        assert_eq!(eval("(= (hash (->Point 1 2)) (hash (->Point 1.0 2)))", &mut env).to_string(), "true");
+        assert_eq!(eval("(count (hash-set (->Point 1 2) (->Point 1 2) (->Size 1 2)))", &mut env).to_string(), "2");
+    }
+}
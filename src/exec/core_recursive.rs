@@ -1,51 +1,48 @@
 use crate::types::list::List;
-use crate::exec::eval::{eval_ast};
+use crate::exec::eval::{eval_ast, eval_args};
 use crate::types::ast::LispValue;
-use crate::exec::closure::create_closure;
-use crate::types::env::Scope;
+use crate::exec::closure::{create_closure, Arity};
+use crate::types::env::{Scope, Doc};
+use crate::types::printer::pr_list;
+use crate::exec::destructure::{bind, validate, param_count};
+use crate::types::function::Function;
+use crate::exec::core_meta::meta_of;
 
 /// lisp let rules are somewhat complicated and this method does not do a good job of making them not compliated.
 pub fn apply_let(list: &List, env: &mut Scope) -> LispValue {
-    if list.len() != 3 {
-        return LispValue::Error("let* two arguments in list".to_string())
+    if list.len() < 3 {
+        return LispValue::Error("usage (let (name value ...) body...)".to_string())
     }
 
     // safe unwrap since we pre-check the length
     match &list[1] {
-        LispValue::List(assignment_list) => {
+        LispValue::List(assignment_list) if assignment_list.len() % 2 == 0 => {
 
             let mut new_scope = env.new_scope();
-            let mut key= "".to_string();
-            let mut rvalue;
-
-            for (i, val) in assignment_list.items().iter().enumerate() {
-                if i % 2  == 0 {
-                    match val {
-                        LispValue::Unit(a) => key = a.token().get_text().clone(),
-                        _ => {
-                            return LispValue::Error("assignment list even argument be string symbol".to_string())
-                        }
-                    }
-                } else {
-                    rvalue = eval_ast(val, &mut new_scope);
 
-                    if let LispValue::Error(e) = rvalue {
-                        return LispValue::Error(e)
-                    }
+            // each pair is a pattern followed by the expression it destructures
+            for pair in assignment_list.to_vec().chunks(2) {
+                let rvalue = eval_ast(&pair[1], &mut new_scope);
+
+                if let LispValue::Error(e) = rvalue {
+                    return LispValue::Error(e)
+                }
 
-                    new_scope.set(key.clone(), rvalue);
+                if let Err(e) = bind(&pair[0], rvalue, &mut new_scope) {
+                    return LispValue::Error(e)
                 }
             }
 
-            eval_ast(&list[2], &mut new_scope)
+            eval_body(list, 2, &mut new_scope)
         }
 
+        LispValue::List(_) => LispValue::Error("let* assignment list needs an even number of forms".to_string()),
         _ => LispValue::Error("first argument to let* must be assignment list".to_string()),
     }
 }
 
 pub fn apply_do(list: &List, env: &mut Scope) -> LispValue {
-    list.items().iter().skip(1).map(|item| {
+    list.iter().skip(1).map(|item| {
         eval_ast(item, env)
     }).last().unwrap_or(LispValue::Nil)
 }
@@ -65,16 +62,16 @@ pub fn apply_if(list: &List, env: &mut Scope) -> LispValue {
     let boolean_flag = eval_ast(&list[1], env);
 
     match boolean_flag {
-        LispValue::Boolean(false) | LispValue::Nil  => {
+        // don't evaluate on error and forward
+        LispValue::Error(s) => LispValue::Error(s),
+
+        flag if !is_truthy(&flag) => {
             match length {
                 4 => eval_ast(&list[3], env),
                 _ => LispValue::Nil
             }
         },
-        // don't evaluate on error and forward
-        LispValue::Error(s) => LispValue::Error(s),
 
-        // everything else is considered "truthy"
         _ => {
             eval_ast(&list[2], env)
         }
@@ -82,49 +79,599 @@ pub fn apply_if(list: &List, env: &mut Scope) -> LispValue {
 
 }
 
-pub fn create_func(list: &List, _env: &mut Scope) -> LispValue {
-    if list.len() != 3 {
-        return LispValue::Error("usage fn* (args list) (body)".to_string());
+/// only false and nil are falsy, everything else (including 0, "" and the empty list) is "truthy"
+pub fn is_truthy(value: &LispValue) -> bool {
+    !matches!(value, LispValue::Boolean(false) | LispValue::Nil)
+}
+
+/// evaluates the rest of the list starting at index from, returning the last value like do
+pub fn eval_body(list: &List, from: usize, env: &mut Scope) -> LispValue {
+    let mut result = LispValue::Nil;
+
+    for item in list.iter().skip(from) {
+        result = eval_ast(item, env);
+
+        if let LispValue::Error(_) = result {
+            break;
+        }
     }
 
-    let mut args = Vec::new();
+    result
+}
+
+/// (and a b ...) stops at the first falsy value and returns it, otherwise the last value. (and) is true
+pub fn apply_and(list: &List, env: &mut Scope) -> LispValue {
+    let mut result = LispValue::Boolean(true);
 
-    match &list[1] {
-        LispValue::List(l) => {
-            for val in l.items() {
-                match val {
-                    LispValue::Unit(v) => {
-                        args.push(v.token().clone());
-                    },
-
-                    _ => return LispValue::Error("function args list must be symbols".to_string())
-                }
+    for item in list.iter().skip(1) {
+        result = eval_ast(item, env);
+
+        if let LispValue::Error(_) = result {
+            return result
+        }
+
+        if !is_truthy(&result) {
+            return result
+        }
+    }
+
+    result
+}
+
+/// (or a b ...) stops at the first truthy value and returns it, otherwise the last value. (or) is nil
+pub fn apply_or(list: &List, env: &mut Scope) -> LispValue {
+    let mut result = LispValue::Nil;
+
+    for item in list.iter().skip(1) {
+        result = eval_ast(item, env);
+
+        if let LispValue::Error(_) = result {
+            return result
+        }
+
+        if is_truthy(&result) {
+            return result
+        }
+    }
+
+    result
+}
+
+pub fn apply_not(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() != 2 {
+        return LispValue::Error("not takes 1 arg".to_string())
+    }
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        value => LispValue::Boolean(!is_truthy(&value))
+    }
+}
+
+fn conditional_body(list: &List, env: &mut Scope, run_when: bool) -> LispValue {
+    if list.len() < 2 {
+        return LispValue::Error("when needs a test".to_string())
+    }
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        test if is_truthy(&test) == run_when => eval_body(list, 2, env),
+        _ => LispValue::Nil
+    }
+}
+
+/// (when test body...) evaluates the body in an implicit do when the test is truthy
+pub fn apply_when(list: &List, env: &mut Scope) -> LispValue {
+    conditional_body(list, env, true)
+}
+
+/// (when-not test body...), also available as unless
+pub fn apply_when_not(list: &List, env: &mut Scope) -> LispValue {
+    conditional_body(list, env, false)
+}
+
+/// (cond test expr test expr ...) evaluates the expression of the first truthy test, :else works as
+/// the catch all since keywords are truthy. nil when nothing matches.
+pub fn apply_cond(list: &List, env: &mut Scope) -> LispValue {
+    if list.len().is_multiple_of(2) {
+        return LispValue::Error("cond needs an even number of forms".to_string())
+    }
+
+    for clause in list.rest().to_vec().chunks(2) {
+        match eval_ast(&clause[0], env) {
+            LispValue::Error(e) => return LispValue::Error(e),
+            test if is_truthy(&test) => return eval_ast(&clause[1], env),
+            _ => ()
+        }
+    }
+
+    LispValue::Nil
+}
+
+/// (case expr constant result ... default?), the constants are not evaluated and a list of constants
+/// matches any of them. without a default a value that matches nothing is an error.
+pub fn apply_case(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() < 2 {
+        return LispValue::Error("case needs an expression to match".to_string())
+    }
+
+    let value = match eval_ast(&list[1], env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        value => value
+    };
+
+    let clauses = list.skip(2).to_vec();
+
+    for clause in clauses.chunks(2) {
+        if clause.len() == 1 {
+            return eval_ast(&clause[0], env)
+        }
+
+        let matched = match &clause[0] {
+            LispValue::List(constants) => constants.iter().any(|constant| *constant == value),
+            constant => *constant == value
+        };
+
+        if matched {
+            return eval_ast(&clause[1], env)
+        }
+    }
+
+    LispValue::Error(format!("no matching case clause for {}", value))
+}
+
+// a ((params) body...) clause of a function with several arities, the params have to be a
+// valid parameter list and the body can't be empty
+fn is_arity_clause(form: &LispValue) -> bool {
+    match form {
+        LispValue::List(l) => l.len() > 1 && matches!(l.first(), Some(params @ LispValue::List(_)) if validate(params).is_ok()),
+        _ => false
+    }
+}
+
+/// splits the forms after lambda or defn into arities, either a single (params) body... or two
+/// or more ((params) body...) clauses, one per parameter count. anything else is read as a single
+/// arity, so (lambda ((a b)) ((f a))) is one arity destructuring its first parameter.
+fn function_arities(forms: &[LispValue], env: &Scope) -> Result<Vec<Arity>, String> {
+    let multiple = forms.len() > 1 && forms.iter().all(is_arity_clause);
+
+    let clauses = if multiple {
+        forms.iter().map(|form| match form {
+            LispValue::List(l) => l.to_vec(),
+            _ => unreachable!()
+        }).collect()
+    } else {
+        vec![forms.to_vec()]
+    };
+
+    let mut arities: Vec<Arity> = Vec::new();
+
+    for clause in clauses {
+        // parameters are a sequential pattern, so (a b & rest) and nested destructuring both work
+        let params = match clause.first() {
+            Some(LispValue::List(l)) => l.clone(),
+            Some(_) => return Err("function args must be a list".to_string()),
+            None => return Err("usage (lambda (args list) body...)".to_string())
+        };
+
+        validate(&clause[0])?;
+
+        let body = &clause[1..];
+
+        for (i, form) in body.iter().enumerate() {
+            check_tail(form, i + 1 == body.len(), env)?;
+        }
+
+        let (fixed, variadic) = param_count(&params);
+
+        for other in &arities {
+            match param_count(&other.params) {
+                (_, true) if variadic => return Err("a function can only have one variadic arity".to_string()),
+                (n, false) if n == fixed && !variadic => {
+                    return Err(format!("a function can only have one arity taking {} args", fixed))
+                },
+                _ => ()
             }
-        },
-        _ => return LispValue::Error("function args must be a list".to_string())
+        }
+
+        arities.push(Arity { params, body: List::from_vec(body.to_vec()) });
     }
 
+    Ok(arities)
+}
+
+// the closure wrapped as a function that remembers its arities
+fn closure_function(arities: Vec<Arity>, name: Option<String>, env: &Scope) -> Function {
+    let counts = arities.iter().map(|arity| param_count(&arity.params)).collect();
+    let mut function = Function::closure(create_closure(arities, name), env);
+    function.set_arities(counts);
 
-    LispValue::Function(create_closure(args, list[2].clone()))
+    function
 }
 
-pub fn apply_def(list: &List,  env: &mut Scope) -> LispValue {
-    if list.len() != 3 {
-        return LispValue::Error("incorrect number of args for definition".to_string());
+/// (lambda (params) body...) or (lambda ((params) body...) ((params) body...)) for several arities
+pub fn create_func(list: &List, env: &mut Scope) -> LispValue {
+    match function_arities(&list.rest().to_vec(), env) {
+        Ok(arities) => LispValue::Function(closure_function(arities, None, env)),
+        Err(e) => LispValue::Error(e)
     }
+}
+
+/// (defn name "docstring"? (params) body...) defines a named function, taking the same
+/// arities as lambda. the name shows up when the function is printed.
+pub fn apply_defn(list: &List, env: &mut Scope) -> LispValue {
+    let name = match list.get(1) {
+        Some(LispValue::Symbol(a)) => a.name().to_string(),
+        _ => return LispValue::Error("usage (defn name docstring? (args list) body...)".to_string())
+    };
+
+    let (doc, from) = match list.get(2) {
+        Some(LispValue::String(doc)) if list.len() > 3 => (Some(doc.clone()), 3),
+        _ => (None, 2)
+    };
+
+    let arities = match function_arities(&list.skip(from).to_vec(), env) {
+        Ok(arities) => arities,
+        Err(e) => return LispValue::Error(e)
+    };
+
+    // the usage line lists every arity, like (fastfibn n) (fastfibn n a b)
+    let usage = arities.iter()
+        .map(|arity| pr_list(&arity.params.cons(list[1].clone()), true))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let mut function = closure_function(arities, Some(name.clone()), env);
+    function.set_name(name.clone());
+
+    let value = LispValue::Function(function);
+    env.define(name, value.clone(), Some(Doc { usage: Some(usage), text: doc }));
+
+    value
+}
+
+/// (def! name "docstring"? value) binds name in the current scope
+pub fn apply_def(list: &List,  env: &mut Scope) -> LispValue {
+    let (doc, value_form) = match list.len() {
+        3 => (None, &list[2]),
+        4 => match &list[2] {
+            LispValue::String(doc) => (Some(doc.clone()), &list[3]),
+            _ => return LispValue::Error("the docstring of def! must be a string".to_string())
+        },
+        _ => return LispValue::Error("incorrect number of args for definition".to_string())
+    };
 
     match &list[1] {
-        LispValue::Unit(a) => {
+        LispValue::Symbol(a) => {
 
             // need to clone to insert into map
-            let key = a.token().get_text().clone();
-            let value = eval_ast(&list[2], env);
+            let key = a.name().to_string();
+            let value = eval_ast(value_form, env);
 
-            env.set(key.clone(), value.clone());
+            // without a docstring, a :doc in the metadata of the value documents it, like ^{:doc "..."} (lambda ...)
+            let doc = doc.or_else(|| match meta_of(&value).and_then(|m| m.get(&LispValue::Keyword("doc".to_string()))) {
+                Some(LispValue::String(text)) => Some(text.clone()),
+                _ => None
+            });
+
+            env.define(key, value.clone(), doc.map(|text| Doc { usage: None, text: Some(text) }));
 
             value
         }
 
         _ => LispValue::Error("first argument to def! must be a symbol".to_string())
     }
-}
\ No newline at end of file
+}
+
+/// (try expr (catch e handler)), when expr evaluates to an error the message is bound to e
+/// while evaluating the handler, any other value is returned as is.
+pub fn apply_try(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() != 3 {
+        return LispValue::Error("usage (try expr (catch e handler))".to_string());
+    }
+
+    let message = match eval_ast(&list[1], env) {
+        LispValue::Error(message) => message,
+        value => return value
+    };
+
+    match &list[2] {
+        LispValue::List(clause) if clause.len() == 3 && clause.first_symbol() == Some("catch") => {
+            match &clause[1] {
+                LispValue::Symbol(a) => {
+                    let mut catch_scope = env.new_scope();
+                    catch_scope.set(a.name().to_string(), LispValue::String(message));
+
+                    eval_ast(&clause[2], &mut catch_scope)
+                },
+                _ => LispValue::Error("catch binding must be a symbol".to_string())
+            }
+        },
+        _ => LispValue::Error("try needs a (catch e handler) clause".to_string())
+    }
+}
+
+/// loops and functions bind this to the number of values recur has to pass back to them,
+/// the space keeps it out of reach of lisp code.
+pub const RECUR_TARGET: &str = " recur";
+
+fn check_all(items: &[LispValue], env: &Scope) -> Result<(), String> {
+    items.iter().try_for_each(|item| check_tail(item, false, env))
+}
+
+/// walks a loop or function body and makes sure recur only shows up in tail position.
+/// nested loops and lambdas are their own recur targets, so we don't look inside them,
+/// and neither do we look inside macro calls since we can't know what they expand to.
+pub fn check_tail(form: &LispValue, tail: bool, env: &Scope) -> Result<(), String> {
+    let list = match form {
+        LispValue::List(l) if !l.is_empty() => l,
+        _ => return Ok(())
+    };
+
+    let items = list.to_vec();
+    let last = items.len() - 1;
+
+    let name = match list.first_symbol() {
+        Some(name) => name,
+        None => return check_all(&items, env)
+    };
+
+    if let Some(LispValue::Macro(_)) = env.get(name) {
+        return Ok(())
+    }
+
+    match name {
+        "recur" if !tail => Err("recur can only be used in tail position".to_string()),
+        "quote" | "lambda" | "defn" | "defmethod" => Ok(()),
+        "loop" => check_all(&items[1..2.min(items.len())], env),
+        "if" => {
+            check_all(&items[1..2.min(items.len())], env)?;
+            items.iter().skip(2).try_for_each(|item| check_tail(item, tail, env))
+        },
+        // for these only the last form is in tail position
+        "do" | "and" | "or" => {
+            items.iter().enumerate().skip(1).try_for_each(|(i, item)| check_tail(item, tail && i == last, env))
+        },
+        "when" | "when-not" | "unless" | "let" => {
+            check_all(&items[1..2.min(items.len())], env)?;
+            items.iter().enumerate().skip(2).try_for_each(|(i, item)| check_tail(item, tail && i == last, env))
+        },
+        "cond" => items.iter().enumerate().skip(1).try_for_each(|(i, item)| {
+            // tests sit at odd indexes, their expressions right after
+            check_tail(item, tail && i % 2 == 0, env)
+        }),
+        "case" => {
+            check_tail(&items[1.min(last)], false, env)?;
+            // results sit at odd indexes, skip the constants in between except for a trailing default
+            items.iter().enumerate().skip(2).try_for_each(|(i, item)| {
+                if i % 2 == 1 || i == last {
+                    check_tail(item, tail, env)
+                } else {
+                    Ok(())
+                }
+            })
+        },
+        _ => check_all(&items[1..], env)
+    }
+}
+
+/// (loop (name value ...) body...) binds like let, then runs the body until it returns anything other
+/// than a recur. recur hands back new values for the bindings, so iterating doesn't grow the stack.
+pub fn apply_loop(list: &List, env: &mut Scope) -> LispValue {
+    if list.len() < 3 {
+        return LispValue::Error("usage (loop (name value ...) body)".to_string())
+    }
+
+    let assignment_list = match &list[1] {
+        LispValue::List(l) if l.len() % 2 == 0 => l,
+        _ => return LispValue::Error("first argument to loop must be an assignment list".to_string())
+    };
+
+    for item in list.iter().skip(2) {
+        if let Err(e) = check_tail(item, true, env) {
+            return LispValue::Error(e)
+        }
+    }
+
+    let mut loop_scope = env.new_scope();
+    let mut patterns = Vec::new();
+
+    let pairs = assignment_list.to_vec();
+
+    for pair in pairs.chunks(2) {
+        let value = match eval_ast(&pair[1], &mut loop_scope) {
+            LispValue::Error(e) => return LispValue::Error(e),
+            value => value
+        };
+
+        if let Err(e) = bind(&pair[0], value, &mut loop_scope) {
+            return LispValue::Error(e)
+        }
+
+        patterns.push(&pair[0]);
+    }
+
+    loop_scope.set(RECUR_TARGET.to_string(), LispValue::Int(patterns.len() as i64));
+
+    loop {
+        match eval_body(list, 2, &mut loop_scope) {
+            LispValue::Recur(values) => {
+                for (pattern, value) in patterns.iter().zip(values) {
+                    if let Err(e) = bind(pattern, value, &mut loop_scope) {
+                        return LispValue::Error(e)
+                    }
+                }
+            },
+            result => return result
+        }
+    }
+}
+
+pub fn apply_recur(list: &List, env: &mut Scope) -> LispValue {
+    let arity = match env.get(RECUR_TARGET) {
+        Some(LispValue::Int(n)) => n as usize,
+        _ => return LispValue::Error("recur can only be used inside a loop or function".to_string())
+    };
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(values) if values.len() != arity => {
+            LispValue::Error(format!("recur expects {} args but got {}", arity, values.len()))
+        },
+        Ok(values) => LispValue::Recur(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+    use std::rc::Rc;
+
+    #[test]
+    fn closures_are_lexically_scoped() {
+        let mut env = Scope::new();
+
+        eval("(def! x 10)", &mut env);
+        eval("(def! show-x (lambda () x))", &mut env);
+        assert_eq!(eval("(let (x 20) (show-x))", &mut env).to_string(), "10");
+
+        eval("(def! adder (let (n 5) (lambda (x) (+ x n))))", &mut env);
+        assert_eq!(eval("(adder 1)", &mut env).to_string(), "6");
+        assert_eq!(eval("(((lambda (a) (lambda (b) (list a b))) 1) 2)", &mut env).to_string(), "(1 2)");
+    }
+
+    #[test]
+    fn let_bound_closures_are_dropped() {
+        let mut env = Scope::new();
+
+        eval("(def! a (atom 0))", &mut env);
+        eval("(let (held a f (lambda () held)) (f))", &mut env);
+        eval("((lambda () (let (held a g (lambda () held)) (g))))", &mut env);
+        eval("(loop (held a h (lambda () held) n 0) (if (< n 2) (recur held h (+ n 1)) (h)))", &mut env);
+        // bound twice, and a closure closing over another one
+        eval("(let (held a f (lambda () held) g f k (lambda () (f))) (list (f) (g) (k)))", &mut env);
+        eval("(defn twice (x) (let (f (lambda () x)) (list (f) (f))))", &mut env);
+        eval("(twice a)", &mut env);
+
+        // only the binding of a and the copy here are left
+        match env.get("a") {
+            Some(LispValue::Atom(a)) => assert_eq!(Rc::strong_count(&a), 2),
+            _ => panic!("a is not an atom")
+        }
+
+        // a closure that escapes keeps its scope
+        eval("(def! f (let (held a) (lambda () held)))", &mut env);
+        assert_eq!(eval("(identical? (f) a)", &mut env).to_string(), "true");
+        // it holds its scope again once it is looked up in it
+        eval("(def! g (let (held a g (lambda () held)) g))", &mut env);
+        assert_eq!(eval("(identical? (g) a)", &mut env).to_string(), "true");
+        eval("(def! box (atom nil))", &mut env);
+        eval("(let (held a h (lambda () held)) (reset! box h))", &mut env);
+        assert_eq!(eval("(identical? (@box) a)", &mut env).to_string(), "true");
+    }
+
+    #[test]
+    fn try_catches_errors() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(try (/ 1 0) (catch e (str \"caught: \" e)))", &mut env).to_string(), "caught: division by zero");
+        assert_eq!(eval("(try (+ 1 2) (catch e :never))", &mut env).to_string(), "3");
+        assert_eq!(eval("(try (undefined-fn) (catch e :caught))", &mut env).to_string(), ":caught");
+
+        // the message is only bound inside the handler
+        assert_eq!(eval("(try (/ 1 0) (catch e (try e (catch f :inner))))", &mut env).to_string(), "division by zero");
+        assert!(matches!(eval("(do (try (/ 1 0) (catch e e)) e)", &mut env), LispValue::Error(_)));
+
+        assert!(matches!(eval("(try (/ 1 0) (catch e (/ 2 0)))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(try (/ 1 0))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(try (/ 1 0) (catch 1 2))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(try (/ 1 0) (finally e 2))", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn and_or_short_circuit() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(and)", &mut env).to_string(), "true");
+        assert_eq!(eval("(and 1 2)", &mut env).to_string(), "2");
+        // the division is never evaluated once a falsy or truthy value decides the result
+        assert_eq!(eval("(and 1 nil (/ 1 0))", &mut env).to_string(), "nil");
+        assert_eq!(eval("(or)", &mut env).to_string(), "nil");
+        assert_eq!(eval("(or nil false 3 (/ 1 0))", &mut env).to_string(), "3");
+        assert_eq!(eval("(not 0)", &mut env).to_string(), "false");
+    }
+
+    #[test]
+    fn conditionals() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(cond (< 2 1) :a (= 1 2) :b :else :c)", &mut env).to_string(), ":c");
+        assert_eq!(eval("(when true 1 2)", &mut env).to_string(), "2");
+        assert_eq!(eval("(when false 1)", &mut env).to_string(), "nil");
+        assert_eq!(eval("(when-not false 3)", &mut env).to_string(), "3");
+    }
+
+    #[test]
+    fn case_matches_constants() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(case 2 1 :one (2 3) :few :many)", &mut env).to_string(), ":few");
+        assert_eq!(eval("(case 9 1 :one :many)", &mut env).to_string(), ":many");
+        assert_eq!(eval("(case \"a\" \"a\" :str)", &mut env).to_string(), ":str");
+        assert!(matches!(eval("(case 9 1 :one)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn loop_recur() {
+        let mut env = Scope::new();
+
+        // deep enough that it would blow the stack if every iteration recursed
+        assert_eq!(eval("(loop (i 0 acc 0) (if (< i 100000) (recur (inc i) (+ acc i)) acc))", &mut env).to_string(),
+                   "4999950000");
+        assert_eq!(eval("((lambda (n acc) (if (zero? n) acc (recur (dec n) (* acc n)))) 20 1)", &mut env).to_string(),
+                   "2432902008176640000");
+        assert_eq!(eval("(loop (i 0) (cond (< i 3) (let (j (inc i)) (recur j)) :else i))", &mut env).to_string(), "3");
+
+        assert!(matches!(eval("(loop (i 0) (+ 1 (recur i)))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(loop (i 0) (if (recur 1) 1 2))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(loop (i 0) (recur 1 2))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(recur 1)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn defn_and_multiple_arities() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(defn fastfibn \"nth fibonacci number\" ((n) (fastfibn n 0 1)) ((n a b) (if (zero? n) a (recur (dec n) b (+ a b)))))", &mut env).to_string(),
+                   "#<fn fastfibn>");
+        assert_eq!(eval("(fastfibn 50)", &mut env).to_string(), "12586269025");
+        assert_eq!(eval("(fastfibn 1 2)", &mut env).to_string(), "error - wrong number of args (2) passed to fastfibn");
+        assert_eq!(eval("((lambda ((x) :one) ((x & more) more)) 1 2 3)", &mut env).to_string(), "(2 3)");
+        assert!(matches!(eval("(lambda ((x) 1) ((y) 2))", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn destructured_first_param_is_a_single_arity() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("((lambda ((a b)) ((lambda () a))) (list 1 2))", &mut env).to_string(), "1");
+        assert_eq!(eval("((lambda ((a b) c) (+ a b c)) (list 1 2) 3)", &mut env).to_string(), "6");
+        // a lone clause is a parameter list that destructures its first arg, not one arity
+        assert_eq!(eval("((lambda ((a) b)) (list 1) 2)", &mut env).to_string(), "nil");
+    }
+
+    #[test]
+    fn implicit_do() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(let (a (atom 0)) (reset! a 1) (deref a))", &mut env).to_string(), "1");
+        assert_eq!(eval("((lambda (a) (def! b (inc a)) (* b 2)) 1)", &mut env).to_string(), "4");
+
+        eval("(def! calls (atom 0))", &mut env);
+        eval("(defn twice (x) (swap! calls inc) (* 2 x))", &mut env);
+        assert_eq!(eval("(twice 4)", &mut env).to_string(), "8");
+        assert_eq!(eval("(deref calls)", &mut env).to_string(), "1");
+    }
+}
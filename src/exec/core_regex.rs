@@ -0,0 +1,171 @@
+use regex::{Captures, Regex};
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::pattern::Pattern;
+use crate::exec::eval::eval_ast;
+use crate::arg_return;
+
+/// patterns can be passed either as a compiled pattern or as a plain string,
+/// strings get compiled on the spot.
+fn to_pattern(value: LispValue) -> Result<Pattern, LispValue> {
+    match value {
+        LispValue::Regex(p) => Ok(p),
+        LispValue::String(s) => Pattern::new(&s).map_err(|e| LispValue::Error(e.to_string())),
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        _ => Err(LispValue::Error("expected a regex pattern".to_string()))
+    }
+}
+
+fn to_string(value: LispValue) -> Result<String, LispValue> {
+    match value {
+        LispValue::String(s) => Ok(s),
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        _ => Err(LispValue::Error("expected a string to match against".to_string()))
+    }
+}
+
+fn pattern_and_string(list: &List, env: &mut Scope) -> Result<(Pattern, String), LispValue> {
+    let pattern = to_pattern(eval_ast(&list[1], env))?;
+    let s = to_string(eval_ast(&list[2], env))?;
+
+    Ok((pattern, s))
+}
+
+/// a match without groups is just the matched string, otherwise we return the full match
+/// followed by each group, with nil for the groups that did not participate.
+fn match_value(regex: &Regex, captures: Captures) -> LispValue {
+    if regex.captures_len() == 1 {
+        return LispValue::String(captures[0].to_string())
+    }
+
+    let groups = captures.iter().map(|group| {
+        match group {
+            Some(m) => LispValue::String(m.as_str().to_string()),
+            None => LispValue::Nil
+        }
+    }).collect();
+
+    LispValue::List(List::from_vec(groups))
+}
+
+pub fn apply_re_pattern(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(re_pattern, 1, list);
+
+    match to_pattern(eval_ast(&list[1], env)) {
+        Ok(p) => LispValue::Regex(p),
+        Err(e) => e
+    }
+}
+
+pub fn apply_re_find(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(re_find, 2, list);
+
+    match pattern_and_string(list, env) {
+        Err(e) => e,
+        Ok((pattern, s)) => match pattern.regex().captures(&s) {
+            Some(c) => match_value(pattern.regex(), c),
+            None => LispValue::Nil
+        }
+    }
+}
+
+pub fn apply_re_matches(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(re_matches, 2, list);
+
+    match pattern_and_string(list, env) {
+        Err(e) => e,
+        Ok((pattern, s)) => match pattern.anchored().captures(&s) {
+            Some(c) => match_value(pattern.anchored(), c),
+            None => LispValue::Nil
+        }
+    }
+}
+
+pub fn apply_re_seq(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(re_seq, 2, list);
+
+    match pattern_and_string(list, env) {
+        Err(e) => e,
+        Ok((pattern, s)) => {
+            let matches = pattern.regex()
+                .captures_iter(&s)
+                .map(|c| match_value(pattern.regex(), c))
+                .collect();
+
+            LispValue::List(List::from_vec(matches))
+        }
+    }
+}
+
+/// (re-replace pattern s replacement), replacement can refer to groups with $1, $name etc.
+pub fn apply_re_replace(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(re_replace, 3, list);
+
+    let (pattern, s) = match pattern_and_string(list, env) {
+        Err(e) => return e,
+        Ok(pair) => pair
+    };
+
+    match to_string(eval_ast(&list[3], env)) {
+        Err(e) => e,
+        Ok(replacement) => {
+            LispValue::String(pattern.regex().replace_all(&s, replacement.as_str()).to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn find_and_seq() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(re-find #\"\\d+\" \"ab 12 cd 34\")", &mut env).to_string(), "12");
+        assert_eq!(eval("(re-find #\"\\d+\" \"no digits\")", &mut env).to_string(), "nil");
+        assert_eq!(eval("(re-find \"[a-z]+\" \"12 ab\")", &mut env).to_string(), "ab");
+        assert_eq!(eval("(re-seq #\"\\d\" \"a1b2c3\")", &mut env).to_string(), "(1 2 3)");
+        assert_eq!(eval("(re-seq #\"\\d\" \"abc\")", &mut env).to_string(), "()");
+    }
+
+    #[test]
+    fn groups() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(pr-str (re-find #\"(\\w+)@(\\w+)\" \"mail bob@home now\"))", &mut env).to_string(),
+                   "(\"bob@home\" \"bob\" \"home\")");
+        assert_eq!(eval("(re-find #\"(a)|(b)\" \"b\")", &mut env).to_string(), "(b nil b)");
+        assert_eq!(eval("(pr-str (re-seq #\"(\\d)(\\w)\" \"1a 2b\"))", &mut env).to_string(),
+                   "((\"1a\" \"1\" \"a\") (\"2b\" \"2\" \"b\"))");
+        assert_eq!(eval("(re-replace #\"(\\w+) (\\w+)\" \"hello world\" \"$2 $1\")", &mut env).to_string(), "world hello");
+    }
+
+    #[test]
+    fn matches_is_anchored() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(re-matches #\"\\d+\" \"123\")", &mut env).to_string(), "123");
+        assert_eq!(eval("(re-matches #\"\\d+\" \"123abc\")", &mut env).to_string(), "nil");
+        assert_eq!(eval("(re-matches #\"\\d+\" \"abc123\")", &mut env).to_string(), "nil");
+        // the anchor wraps the whole pattern, so an alternative can't match just a part
+        assert_eq!(eval("(re-matches #\"a|ab\" \"ab\")", &mut env).to_string(), "ab");
+        assert_eq!(eval("(re-matches #\"(\\d)-(\\d)\" \"1-2\")", &mut env).to_string(), "(1-2 1 2)");
+    }
+
+    #[test]
+    fn patterns_and_errors() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(pr-str (re-pattern \"a+\"))", &mut env).to_string(), "#\"a+\"");
+        assert_eq!(eval("(regex? (re-pattern #\"a+\"))", &mut env).to_string(), "true");
+        assert!(matches!(eval("(re-pattern \"(unclosed\")", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(re-find \"[\" \"x\")", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(re-find #\"a\" 1)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(re-find 1 \"a\")", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(re-find #\"a\")", &mut env), LispValue::Error(_)));
+    }
+}
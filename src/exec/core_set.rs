@@ -0,0 +1,207 @@
+use crate::types::list::List;
+use crate::types::set::Set;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::printer::pr_str;
+use crate::exec::eval::eval_args;
+use crate::arg_return;
+
+/// nil works as the empty set, like it does in clojure
+fn to_set(value: &LispValue, fname: &str) -> Result<Set, LispValue> {
+    match value {
+        LispValue::Set(s) => Ok(s.clone()),
+        LispValue::Nil => Ok(Set::new()),
+        _ => Err(LispValue::Error(format!("{} needs a set", fname)))
+    }
+}
+
+fn sets(list: &List, env: &mut Scope, fname: &str) -> Result<Vec<Set>, LispValue> {
+    eval_args(list, env)?.iter().map(|arg| to_set(arg, fname)).collect()
+}
+
+pub fn apply_hash_set(list: &List, env: &mut Scope) -> LispValue {
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Set(Set::from_vec(args))
+    }
+}
+
+pub fn apply_is_set(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_set, 1, list);
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::Boolean(matches!(args[0], LispValue::Set(_)))
+    }
+}
+
+/// (conj coll x ...) adds to a collection the way that suits it: sets add the elements,
+/// lists (and nil) get them in front and maps take (key value) entries.
+pub fn apply_conj(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    let (coll, items) = match args.split_first() {
+        Some(split) => split,
+        None => return LispValue::Error("conj needs a collection".to_string())
+    };
+
+    match coll {
+        LispValue::Set(s) => {
+            let mut s = s.clone();
+            items.iter().for_each(|item| { s.insert(item.clone()); });
+
+            LispValue::Set(s)
+        },
+        LispValue::List(_) | LispValue::Nil => {
+            let rest = match coll {
+                LispValue::List(l) => l.with_meta(None),
+                _ => List::new()
+            };
+
+            LispValue::List(items.iter().fold(rest, |rest, item| rest.cons(item.clone())))
+        },
+        LispValue::Map(m) => {
+            let mut m = m.clone();
+
+            for item in items {
+                match item {
+                    LispValue::List(entry) if entry.len() == 2 => m.insert(entry[0].clone(), entry[1].clone()),
+                    other => return LispValue::Error(format!("conj on a map needs (key value) entries, got {}", pr_str(other, true)))
+                }
+            }
+
+            LispValue::Map(m)
+        },
+        other => LispValue::Error(format!("cannot conj onto {}", pr_str(other, true)))
+    }
+}
+
+/// (disj set x ...)
+pub fn apply_disj(list: &List, env: &mut Scope) -> LispValue {
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    match args.split_first() {
+        None => LispValue::Error("disj needs a set".to_string()),
+        Some((coll, items)) => match to_set(coll, "disj") {
+            Err(e) => e,
+            Ok(mut s) => {
+                items.iter().for_each(|item| s.remove(item));
+
+                LispValue::Set(s)
+            }
+        }
+    }
+}
+
+/// (contains? coll key) checks the elements of a set or the keys of a map
+pub fn apply_contains(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(contains, 2, list);
+
+    let args = match eval_args(list, env) {
+        Err(e) => return e,
+        Ok(args) => args
+    };
+
+    match &args[0] {
+        LispValue::Set(s) => LispValue::Boolean(s.contains(&args[1])),
+        LispValue::Map(m) => LispValue::Boolean(m.contains_key(&args[1])),
+        LispValue::Record(r) => LispValue::Boolean(r.fields().contains_key(&args[1])),
+        LispValue::Nil => LispValue::Boolean(false),
+        other => LispValue::Error(format!("contains? needs a set or a map, got {}", pr_str(other, true)))
+    }
+}
+
+pub fn apply_union(list: &List, env: &mut Scope) -> LispValue {
+    match sets(list, env, "union") {
+        Err(e) => e,
+        Ok(sets) => LispValue::Set(Set::from_vec(sets.iter().flat_map(|s| s.iter().cloned()).collect()))
+    }
+}
+
+/// elements of the first set that are in every other set
+pub fn apply_intersection(list: &List, env: &mut Scope) -> LispValue {
+    match sets(list, env, "intersection") {
+        Err(e) => e,
+        Ok(sets) => match sets.split_first() {
+            None => LispValue::Error("intersection needs at least one set".to_string()),
+            Some((first, others)) => LispValue::Set(Set::from_vec(first.iter()
+                .filter(|item| others.iter().all(|s| s.contains(item)))
+                .cloned()
+                .collect()))
+        }
+    }
+}
+
+/// elements of the first set that are in none of the others
+pub fn apply_difference(list: &List, env: &mut Scope) -> LispValue {
+    match sets(list, env, "difference") {
+        Err(e) => e,
+        Ok(sets) => match sets.split_first() {
+            None => LispValue::Error("difference needs at least one set".to_string()),
+            Some((first, others)) => LispValue::Set(Set::from_vec(first.iter()
+                .filter(|item| !others.iter().any(|s| s.contains(item)))
+                .cloned()
+                .collect()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn set_literals() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("#{1 (+ 1 1) :a}", &mut env).to_string(), "#{1 2 :a}");
+        assert_eq!(eval("(= #{1 2 3} (hash-set 3 2 1 1))", &mut env).to_string(), "true");
+        assert_eq!(eval("(set? #{})", &mut env).to_string(), "true");
+        assert_eq!(eval("(set? (list))", &mut env).to_string(), "false");
+        assert_eq!(eval("(contains? #{(list 1 2) {:a 1}} (list 1 2))", &mut env).to_string(), "true");
+        assert_eq!(eval("(contains? #{1} 1.0)", &mut env).to_string(), "true");
+        assert!(matches!(read_string(&Tokenizer::new(), "#{1 1}".to_string()), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn set_operations() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(conj #{1} 2 1)", &mut env).to_string(), "#{1 2}");
+        assert_eq!(eval("(disj #{1 2 3} 2 4)", &mut env).to_string(), "#{1 3}");
+        assert_eq!(eval("(union #{1 2} #{2 3})", &mut env).to_string(), "#{1 2 3}");
+        assert_eq!(eval("(intersection #{1 2 3} #{2 3 4} #{3 2})", &mut env).to_string(), "#{2 3}");
+        assert_eq!(eval("(difference #{1 2 3} #{2} #{3})", &mut env).to_string(), "#{1}");
+        assert!(matches!(eval("(union #{1} (list 1))", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn conj_onto_lists_and_maps() {
+        let mut env = Scope::new();
+
+        // lists get the items in front, sharing the list they were added to
+        eval("(def! l (list 2 3))", &mut env);
+        assert_eq!(eval("(conj l 1 0)", &mut env).to_string(), "(0 1 2 3)");
+        assert_eq!(eval("(identical? (rest (conj l 1)) l)", &mut env).to_string(), "true");
+        assert_eq!(eval("(conj nil 1)", &mut env).to_string(), "(1)");
+
+        // maps take (key value) entries, replacing the value of a key that is there
+        assert_eq!(eval("(conj {:a 1} (list :b 2))", &mut env).to_string(), "{:a 1 :b 2}");
+        assert_eq!(eval("(conj {:a 1} (list :a 3) (list :c 4))", &mut env).to_string(), "{:a 3 :c 4}");
+        assert!(matches!(eval("(conj {:a 1} :b)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(conj {:a 1} (list :b))", &mut env), LispValue::Error(_)));
+
+        assert!(matches!(eval("(conj 1 2)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(conj)", &mut env), LispValue::Error(_)));
+    }
+}
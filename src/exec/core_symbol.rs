@@ -0,0 +1,94 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::symbol::Symbol;
+use crate::types::printer::pr_str;
+use crate::exec::eval::eval_ast;
+use crate::arg_return;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// every gensym takes the next number, so generated names never repeat within a run
+static GENSYM_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// (symbol name) makes a symbol out of a string, keyword or symbol
+pub fn apply_symbol(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(symbol, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        LispValue::String(s) | LispValue::Keyword(s) if !s.is_empty() => LispValue::Symbol(Symbol::new(&s)),
+        symbol @ LispValue::Symbol(_) => symbol,
+        other => LispValue::Error(format!("cannot make a symbol out of {}", pr_str(&other, true)))
+    }
+}
+
+pub fn apply_is_symbol(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_symbol, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        value => LispValue::Boolean(matches!(value, LispValue::Symbol(_)))
+    }
+}
+
+/// (name x) is the name of a symbol or keyword as a string, strings are their own name
+pub fn apply_name(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(name, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        LispValue::Symbol(s) => LispValue::String(s.name().to_string()),
+        LispValue::Keyword(s) | LispValue::String(s) => LispValue::String(s),
+        other => LispValue::Error(format!("{} doesn't have a name", pr_str(&other, true)))
+    }
+}
+
+/// (gensym prefix?) returns a fresh symbol like G__12 for macros to bind temporaries to
+pub fn apply_gensym(list: &List, env: &mut Scope) -> LispValue {
+    let prefix = match list.len() {
+        1 => "G__".to_string(),
+        2 => match eval_ast(&list[1], env) {
+            LispValue::Error(e) => return LispValue::Error(e),
+            LispValue::String(s) => s,
+            LispValue::Symbol(s) => s.name().to_string(),
+            _ => return LispValue::Error("the gensym prefix must be a string".to_string())
+        },
+        _ => return LispValue::Error("usage (gensym prefix?)".to_string())
+    };
+
+    let id = GENSYM_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    LispValue::Symbol(Symbol::new(&format!("{}{}", prefix, id)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn symbols_are_values() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(quote foo)", &mut env).to_string(), "foo");
+        assert_eq!(eval("(symbol? 'foo)", &mut env).to_string(), "true");
+        assert_eq!(eval("(symbol? \"foo\")", &mut env).to_string(), "false");
+        assert_eq!(eval("(= 'foo (symbol \"foo\"))", &mut env).to_string(), "true");
+        assert_eq!(eval("(= 'foo \"foo\")", &mut env).to_string(), "false");
+        assert_eq!(eval("(pr-str (name 'foo))", &mut env).to_string(), "\"foo\"");
+        assert_eq!(eval("(pr-str (name :bar))", &mut env).to_string(), "\"bar\"");
+        assert_eq!(eval("(pr-str (symbol :baz))", &mut env).to_string(), "baz");
+        assert_eq!(eval("(pr-str 'foo \"foo\")", &mut env).to_string(), "foo \"foo\"");
+        assert!(matches!(eval("(symbol 1)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn gensym_is_unique() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(= (gensym) (gensym))", &mut env).to_string(), "false");
+        assert!(eval("(name (gensym \"tmp\"))", &mut env).to_string().starts_with("tmp"));
+        assert_eq!(eval("(eval (list 'let (list (gensym) 1) 2))", &mut env).to_string(), "2");
+    }
+}
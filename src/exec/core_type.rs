@@ -0,0 +1,196 @@
+use crate::types::list::List;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::symbol::Symbol;
+use crate::exec::eval::{eval_ast, eval_args};
+use crate::arg_return;
+
+/// every name type_of gives a built-in value, record types can't take them
+pub const KIND_NAMES: [&str; 20] = ["list", "lazy-seq", "map", "set", "symbol", "int", "bigint", "ratio", "float",
+    "nil", "boolean", "fn", "error", "string", "char", "keyword", "atom", "macro", "regex", "recur"];
+
+/// the keyword type answers with, named after the variant. a record answers with the name of its
+/// type instead, so multimethods dispatching on type can tell record types apart.
+pub fn type_of(value: &LispValue) -> LispValue {
+    let name = match value {
+        LispValue::List(_) => "list",
+        LispValue::LazySeq(_) => "lazy-seq",
+        LispValue::Map(_) => "map",
+        LispValue::Set(_) => "set",
+        LispValue::Record(r) => r.name(),
+        LispValue::Symbol(_) => "symbol",
+        LispValue::Int(_) => "int",
+        LispValue::BigInt(_) => "bigint",
+        LispValue::Ratio(_) => "ratio",
+        LispValue::Float(_) => "float",
+        LispValue::Nil => "nil",
+        LispValue::Boolean(_) => "boolean",
+        LispValue::Function(_) => "fn",
+        LispValue::Error(_) => "error",
+        LispValue::String(_) => "string",
+        LispValue::Char(_) => "char",
+        LispValue::Keyword(_) => "keyword",
+        LispValue::Atom(_) => "atom",
+        LispValue::Macro(_) => "macro",
+        LispValue::Regex(_) => "regex",
+        LispValue::Recur(_) => "recur"
+    };
+
+    LispValue::Keyword(name.to_string())
+}
+
+/// (type x) is a keyword naming what kind of value x is, like :int, :list or :fn
+pub fn apply_type(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(type, 1, list);
+
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => type_of(&args[0])
+    }
+}
+
+// type checks accept any value
+macro_rules! type_predicate {
+    ($func:ident, $name:ident, $($pattern:pat)|+) => {
+        pub fn $func(list: &List, env: &mut Scope) -> LispValue {
+            arg_return!($name, 1, list);
+
+            match eval_ast(&list[1], env) {
+                LispValue::Error(e) => LispValue::Error(e),
+                value => LispValue::Boolean(matches!(value, $($pattern)|+))
+            }
+        }
+    }
+}
+
+type_predicate!(apply_is_int, is_int, LispValue::Int(_) | LispValue::BigInt(_));
+type_predicate!(apply_is_bigint, is_bigint, LispValue::BigInt(_));
+type_predicate!(apply_is_ratio, is_ratio, LispValue::Ratio(_));
+type_predicate!(apply_is_float, is_float, LispValue::Float(_));
+type_predicate!(apply_is_number, is_number,
+                LispValue::Int(_) | LispValue::BigInt(_) | LispValue::Ratio(_) | LispValue::Float(_));
+type_predicate!(apply_is_nil, is_nil, LispValue::Nil);
+type_predicate!(apply_is_some, is_some, LispValue::Symbol(_) | LispValue::Int(_) | LispValue::BigInt(_)
+                | LispValue::Ratio(_) | LispValue::Float(_) | LispValue::Boolean(_) | LispValue::String(_)
+                | LispValue::Char(_) | LispValue::Keyword(_) | LispValue::List(_) | LispValue::LazySeq(_)
+                | LispValue::Map(_) | LispValue::Set(_) | LispValue::Record(_) | LispValue::Function(_)
+                | LispValue::Macro(_) | LispValue::Atom(_) | LispValue::Regex(_));
+type_predicate!(apply_is_boolean, is_boolean, LispValue::Boolean(_));
+type_predicate!(apply_is_true, is_true, LispValue::Boolean(true));
+type_predicate!(apply_is_false, is_false, LispValue::Boolean(false));
+type_predicate!(apply_is_string, is_string, LispValue::String(_));
+type_predicate!(apply_is_keyword, is_keyword, LispValue::Keyword(_));
+type_predicate!(apply_is_regex, is_regex, LispValue::Regex(_));
+type_predicate!(apply_is_list, is_list, LispValue::List(_));
+type_predicate!(apply_is_lazy_seq, is_lazy_seq, LispValue::LazySeq(_));
+type_predicate!(apply_is_seq, is_seq, LispValue::List(_) | LispValue::LazySeq(_));
+type_predicate!(apply_is_coll, is_coll, LispValue::List(_) | LispValue::LazySeq(_) | LispValue::Map(_)
+                | LispValue::Set(_) | LispValue::Record(_));
+type_predicate!(apply_is_fn, is_fn, LispValue::Function(_));
+type_predicate!(apply_is_macro, is_macro, LispValue::Macro(_));
+type_predicate!(apply_is_callable, is_callable, LispValue::Function(_) | LispValue::Macro(_));
+
+/// (arity f) lists how many args each arity of f takes, in order, with & after the count of the
+/// arity taking a rest parameter. nil for builtins, which check their args themselves.
+pub fn apply_arity(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(arity, 1, list);
+
+    let function = match eval_ast(&list[1], env) {
+        LispValue::Error(e) => return LispValue::Error(e),
+        LispValue::Function(f) | LispValue::Macro(f) => f,
+        other => return LispValue::Error(format!("arity needs a function but got {}", type_of(&other)))
+    };
+
+    match function.arities() {
+        None => LispValue::Nil,
+        Some(arities) => {
+            let mut counts = Vec::new();
+
+            for (fixed, variadic) in arities {
+                counts.push(LispValue::Int(*fixed as i64));
+
+                if *variadic {
+                    counts.push(LispValue::Symbol(Symbol::new("&")));
+                }
+            }
+
+            LispValue::List(List::from_vec(counts))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn type_names_the_variant() {
+        let mut env = Scope::new();
+
+        let cases = vec![
+            ("1", ":int"), ("1.5", ":float"), ("1/2", ":ratio"), ("\"s\"", ":string"), ("\\a", ":char"),
+            (":k", ":keyword"), ("'x", ":symbol"), ("(list)", ":list"), ("{}", ":map"), ("#{}", ":set"),
+            ("nil", ":nil"), ("true", ":boolean"), ("inc", ":fn"), ("(range 1)", ":lazy-seq"), ("(atom 1)", ":atom"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(eval(&format!("(type {})", value), &mut env).to_string(), expected, "{}", value);
+        }
+
+        eval("(defrecord Point (x y))", &mut env);
+        assert_eq!(eval("(type (->Point 1 2))", &mut env).to_string(), ":Point");
+
+        // a record named after a built-in type would make type ambiguous
+        assert!(matches!(eval("(defrecord map (a))", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(defrecord fn (a))", &mut env), LispValue::Error(_)));
+        assert_eq!(eval("(type {})", &mut env).to_string(), ":map");
+    }
+
+    #[test]
+    fn predicates_cover_every_kind_of_value() {
+        let mut env = Scope::new();
+        eval("(defmacro! m (lambda (x) x))", &mut env);
+
+        let values = "(list nil true false 1 100000000000000000000 1/2 1.5 \"s\" :k 'x (list 1) (range 2) {} #{} (atom 1) inc m (re-pattern \"a\"))";
+        let cases = vec![
+            ("nil?", "(true false false false false false false false false false false false false false false false false false)"),
+            ("some?", "(false true true true true true true true true true true true true true true true true true)"),
+            ("boolean?", "(false true true false false false false false false false false false false false false false false false)"),
+            ("true?", "(false true false false false false false false false false false false false false false false false false)"),
+            ("false?", "(false false true false false false false false false false false false false false false false false false)"),
+            ("number?", "(false false false true true true true false false false false false false false false false false false)"),
+            ("bigint?", "(false false false false true false false false false false false false false false false false false false)"),
+            ("ratio?", "(false false false false false true false false false false false false false false false false false false)"),
+            ("string?", "(false false false false false false false true false false false false false false false false false false)"),
+            ("keyword?", "(false false false false false false false false true false false false false false false false false false)"),
+            ("list?", "(false false false false false false false false false false true false false false false false false false)"),
+            ("lazy-seq?", "(false false false false false false false false false false false true false false false false false false)"),
+            ("seq?", "(false false false false false false false false false false true true false false false false false false)"),
+            ("coll?", "(false false false false false false false false false false true true true true false false false false)"),
+            ("fn?", "(false false false false false false false false false false false false false false false true false false)"),
+            ("macro?", "(false false false false false false false false false false false false false false false false true false)"),
+            ("callable?", "(false false false false false false false false false false false false false false false true true false)"),
+            ("regex?", "(false false false false false false false false false false false false false false false false false true)"),
+        ];
+
+        for (predicate, expected) in cases {
+            let code = format!("(map (lambda (v) ({} v)) {})", predicate, values);
+            assert_eq!(eval(&code, &mut env).to_string(), expected, "{}", predicate);
+        }
+    }
+
+    #[test]
+    fn arity_lists_the_arg_counts() {
+        let mut env = Scope::new();
+
+        eval("(defn f ((a) a) ((a b & more) b))", &mut env);
+
+        assert_eq!(eval("(arity (lambda (x y) x))", &mut env).to_string(), "(2)");
+        assert_eq!(eval("(arity f)", &mut env).to_string(), "(1 2 &)");
+        assert_eq!(eval("(arity (lambda () 1))", &mut env).to_string(), "(0)");
+        assert_eq!(eval("(arity +)", &mut env).to_string(), "nil");
+        assert!(matches!(eval("(arity 1)", &mut env), LispValue::Error(_)));
+    }
+}
@@ -1,18 +1,18 @@
 use crate::types::list::List;
 use crate::types::ast::{LispValue, build_ast};
 use crate::types::env::Scope;
-use crate::exec::eval::eval_ast;
+use crate::exec::eval::{eval_ast, eval_args};
+use crate::types::printer::pr_seq;
 use crate::reader::tokenizer::Tokenizer;
 use crate::reader::parser::Parser;
 
-pub fn apply_list(list: &List, _env: &mut Scope) -> LispValue {
-    let mut copied = List::new();
-
-    list.items().iter().skip(1).for_each(|item| {
-        copied.push(item.clone())
-    });
-
-    LispValue::List(copied)
+/// (list & items) evaluates its items like any other function call does with its args, so
+/// (list x (+ 1 2)) holds the value of x and 3. quote a form to keep it as it was read.
+pub fn apply_list(list: &List, env: &mut Scope) -> LispValue {
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(items) => LispValue::List(List::from_vec(items))
+    }
 }
 
 pub fn apply_eval(list: &List, env: &mut Scope) -> LispValue {
@@ -24,29 +24,24 @@ pub fn apply_eval(list: &List, env: &mut Scope) -> LispValue {
         // then we eval again to evaluate that item as code
         let first_result = eval_ast(&list[1], env);
 
-        let new_env = env.root();
-
-        match new_env {
-            None => LispValue::Error("could not find root environment for eval".to_string()),
-            Some( mut e) => eval_ast(&first_result, &mut e)
-        }
+        // at the top level of the namespace, the root only holds the builtins
+        eval_ast(&first_result, &mut env.namespace_scope())
     }
 }
 
+/// str concatenates the display form of its arguments, pr-str joins the readable forms with spaces
 pub fn apply_str(list: &List, env: &mut Scope) -> LispValue {
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::String(pr_seq(&args, false, ""))
+    }
+}
 
-    let mut out = list.items().iter().skip(1).map(|item| {
-        let converted = eval_ast(item, env);
-        format!("{} ", converted)
-    }).fold("".to_string(), |mut total, next | {
-        total.push_str(next.as_str());
-
-        total
-    });
-
-    out.pop();
-
-    LispValue::String(out)
+pub fn apply_pr_str(list: &List, env: &mut Scope) -> LispValue {
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => LispValue::String(pr_seq(&args, true, " "))
+    }
 }
 
 pub fn read_string(tokenizer: &Tokenizer, line: String) -> LispValue {
@@ -78,12 +73,39 @@ pub fn apply_read_string(list: &List, env: &mut Scope) -> LispValue {
 }
 
 pub fn apply_prn(list: &List, env: &mut Scope) -> LispValue {
-    if list.len() != 2 {
-        LispValue::Error("prn takes a single argument".to_string())
-    } else {
-        println!("{}", eval_ast(&list[1], env));
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => {
+            println!("{}", pr_seq(&args, true, " "));
+
+            LispValue::Nil
+        }
+    }
+}
+
+pub fn apply_println(list: &List, env: &mut Scope) -> LispValue {
+    match eval_args(list, env) {
+        Err(e) => e,
+        Ok(args) => {
+            println!("{}", pr_seq(&args, false, " "));
 
-        LispValue::Nil
+            LispValue::Nil
+        }
     }
 }
 
+
+#[cfg(test)]
+mod test {
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn list_evaluates_its_items() {
+        let mut env = Scope::new();
+        eval("(def! x 1)", &mut env);
+
+        assert_eq!(eval("(list x (+ 1 2))", &mut env).to_string(), "(1 3)");
+        assert_eq!(eval("(list 'x '(+ 1 2))", &mut env).to_string(), "(x (+ 1 2))");
+    }
+}
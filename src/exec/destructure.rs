@@ -0,0 +1,339 @@
+use crate::types::list::List;
+use crate::types::map::Map;
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::types::printer::pr_str;
+use crate::types::lazy::seq_step;
+use crate::exec::eval::eval_ast;
+
+/// the parts of a sequential pattern like (a (b c) & rest :as all)
+struct SequencePattern {
+    fixed: Vec<LispValue>,
+    rest: Option<LispValue>,
+    whole: Option<LispValue>
+}
+
+fn is_symbol(value: &LispValue, name: &str) -> bool {
+    matches!(value, LispValue::Symbol(a) if a.name() == name)
+}
+
+fn is_keyword(value: &LispValue, name: &str) -> bool {
+    matches!(value, LispValue::Keyword(k) if k == name)
+}
+
+fn parse_sequence(pattern: &List) -> Result<SequencePattern, String> {
+    let mut items = pattern.to_vec();
+
+    let whole = match items.len() {
+        n if n >= 2 && is_keyword(&items[n - 2], "as") => {
+            let whole = items.pop();
+            items.pop();
+            whole
+        },
+        _ => None
+    };
+
+    let rest = match items.iter().position(|item| is_symbol(item, "&")) {
+        None => None,
+        Some(i) if i + 2 == items.len() => {
+            let rest = items.pop();
+            items.pop();
+            rest
+        },
+        Some(_) => return Err("& must be followed by exactly one pattern".to_string())
+    };
+
+    if let Some(w) = &whole {
+        if !matches!(w, LispValue::Symbol(_)) {
+            return Err(":as must be followed by a symbol".to_string())
+        }
+    }
+
+    items.iter().chain(&rest).try_for_each(validate)?;
+
+    Ok(SequencePattern { fixed: items, rest, whole })
+}
+
+fn validate_map(pattern: &Map) -> Result<(), String> {
+    for (key, value) in pattern.iter() {
+        match key {
+            LispValue::Keyword(k) if k == "keys" => match value {
+                LispValue::List(l) if l.iter().all(|s| matches!(s, LispValue::Symbol(_))) => (),
+                _ => return Err(":keys must be followed by a list of symbols".to_string())
+            },
+            LispValue::Keyword(k) if k == "or" => match value {
+                LispValue::Map(_) => (),
+                _ => return Err(":or must be followed by a map of defaults".to_string())
+            },
+            LispValue::Keyword(k) if k == "as" => match value {
+                LispValue::Symbol(_) => (),
+                _ => return Err(":as must be followed by a symbol".to_string())
+            },
+            binding => validate(binding)?
+        }
+    }
+
+    Ok(())
+}
+
+/// checks the shape of a binding pattern without binding anything,
+/// so lambdas can report bad parameter lists when they are created.
+pub fn validate(pattern: &LispValue) -> Result<(), String> {
+    match pattern {
+        LispValue::Symbol(_) => Ok(()),
+        LispValue::List(l) => parse_sequence(l).map(|_| ()),
+        LispValue::Map(m) => validate_map(m),
+        _ => Err(format!("cannot bind to {}", pr_str(pattern, true)))
+    }
+}
+
+/// binds the symbols in pattern to the matching parts of value. patterns are either a symbol,
+/// a list destructuring a list, or a map destructuring a map by key.
+pub fn bind(pattern: &LispValue, value: LispValue, scope: &mut Scope) -> Result<(), String> {
+    match pattern {
+        LispValue::Symbol(a) => {
+            scope.set(a.name().to_string(), value);
+            Ok(())
+        },
+        LispValue::List(l) => bind_sequence(l, value, scope),
+        LispValue::Map(m) => bind_map(m, value, scope),
+        _ => Err(format!("cannot bind to {}", pr_str(pattern, true)))
+    }
+}
+
+/// the first n items of a lazy seq and what is left after them, nil when nothing is. only the
+/// steps that are needed are realized, so an infinite seq can be destructured.
+fn lazy_prefix(seq: &LispValue, n: usize) -> Result<(Vec<LispValue>, LispValue), String> {
+    let mut items = Vec::with_capacity(n);
+    let mut remaining = seq.clone();
+
+    while items.len() < n {
+        match seq_step(&remaining)? {
+            None => return Ok((items, LispValue::Nil)),
+            Some((first, rest)) => {
+                items.push(first);
+                remaining = rest;
+            }
+        }
+    }
+
+    match seq_step(&remaining)? {
+        None => Ok((items, LispValue::Nil)),
+        Some(_) => Ok((items, remaining))
+    }
+}
+
+fn bind_sequence(pattern: &List, value: LispValue, scope: &mut Scope) -> Result<(), String> {
+    let parsed = parse_sequence(pattern)?;
+    let fixed = parsed.fixed.len();
+
+    // the items for the fixed patterns, what is left for the rest and how many there are in all if that is known
+    let (items, remaining, count) = match &value {
+        // like clojure, an empty rest is nil rather than an empty list. the rest shares the cells of the value
+        LispValue::List(l) => (l.iter().take(fixed).cloned().collect(), match l.skip(fixed) {
+            values if values.is_empty() => LispValue::Nil,
+            values => LispValue::List(values)
+        }, Some(l.len())),
+        LispValue::Nil => (Vec::new(), LispValue::Nil, Some(0)),
+        LispValue::LazySeq(_) => {
+            let (items, remaining) = lazy_prefix(&value, fixed)?;
+            let count = if matches!(remaining, LispValue::Nil) { Some(items.len()) } else { None };
+
+            (items, remaining, count)
+        },
+        other => return Err(format!("expected a list to destructure but got {}", pr_str(other, true)))
+    };
+
+    if items.len() < fixed || (parsed.rest.is_none() && !matches!(remaining, LispValue::Nil)) {
+        let expected = if parsed.rest.is_some() { format!("at least {}", fixed) } else { fixed.to_string() };
+        let got = count.map(|n| n.to_string()).unwrap_or_else(|| "more".to_string());
+
+        return Err(format!("expected {} values to destructure but got {}", expected, got))
+    }
+
+    for (p, v) in parsed.fixed.iter().zip(items) {
+        bind(p, v, scope)?;
+    }
+
+    if let Some(rest) = &parsed.rest {
+        bind(rest, remaining, scope)?;
+    }
+
+    if let Some(whole) = &parsed.whole {
+        bind(whole, value, scope)?;
+    }
+
+    Ok(())
+}
+
+fn bind_map(pattern: &Map, value: LispValue, scope: &mut Scope) -> Result<(), String> {
+    validate_map(pattern)?;
+
+    let map = match &value {
+        LispValue::Map(m) => m.clone(),
+        LispValue::Record(r) => r.fields().clone(),
+        LispValue::Nil => Map::new(),
+        other => return Err(format!("expected a map to destructure but got {}", pr_str(other, true)))
+    };
+
+    let defaults = match pattern.get(&LispValue::Keyword("or".to_string())) {
+        Some(LispValue::Map(m)) => m.clone(),
+        _ => Map::new()
+    };
+
+    for (key, lookup) in pattern.iter() {
+        match key {
+            LispValue::Keyword(k) if k == "keys" => {
+                if let LispValue::List(symbols) = lookup {
+                    for symbol in symbols {
+                        if let LispValue::Symbol(a) = symbol {
+                            let found = map.get(&LispValue::Keyword(a.name().to_string())).cloned();
+                            bind_or_default(symbol, found, &defaults, scope)?;
+                        }
+                    }
+                }
+            },
+            LispValue::Keyword(k) if k == "or" => (),
+            LispValue::Keyword(k) if k == "as" => bind(lookup, value.clone(), scope)?,
+            binding => bind_or_default(binding, map.get(lookup).cloned(), &defaults, scope)?
+        }
+    }
+
+    Ok(())
+}
+
+/// missing keys fall back to the :or default for the symbol, which is evaluated only when needed, or nil
+fn bind_or_default(pattern: &LispValue, found: Option<LispValue>, defaults: &Map, scope: &mut Scope) -> Result<(), String> {
+    let value = match found {
+        Some(v) => v,
+        None => match defaults.get(pattern) {
+            Some(default) => match eval_ast(default, scope) {
+                LispValue::Error(e) => return Err(e),
+                v => v
+            },
+            None => LispValue::Nil
+        }
+    };
+
+    bind(pattern, value, scope)
+}
+
+/// the number of fixed parameters and whether a rest parameter takes any values beyond them
+pub fn param_count(params: &List) -> (usize, bool) {
+    match parse_sequence(params) {
+        Ok(parsed) => (parsed.fixed.len(), parsed.rest.is_some()),
+        Err(_) => (params.len(), false)
+    }
+}
+
+/// number of values recur has to pass for a parameter list, a rest parameter counts as one
+pub fn recur_arity(params: &List) -> usize {
+    let (fixed, variadic) = param_count(params);
+
+    fixed + variadic as usize
+}
+
+/// recur passes the rest parameter as a single list, so splice it back in before binding
+pub fn bind_recur(params: &List, mut values: Vec<LispValue>, scope: &mut Scope) -> Result<(), String> {
+    let parsed = parse_sequence(params)?;
+
+    if parsed.rest.is_some() {
+        match values.pop() {
+            Some(LispValue::List(rest)) => values.extend(rest.iter().cloned()),
+            Some(LispValue::Nil) | None => (),
+            Some(other) => values.push(other)
+        }
+    }
+
+    bind(&LispValue::List(params.clone()), LispValue::List(List::from_vec(values)), scope)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    // what name is bound to when pattern is destructured against value in a let
+    fn bound(pattern: &str, value: &str, name: &str) -> String {
+        eval(&format!("(let ({} {}) {})", pattern, value, name), &mut Scope::new()).to_string()
+    }
+
+    #[test]
+    fn sequential_destructuring() {
+        let nested = "(list 1 (list 2 3) 4 5)";
+
+        assert_eq!(bound("(a (b c) & rest)", nested, "a"), "1");
+        assert_eq!(bound("(a (b c) & rest)", nested, "c"), "3");
+        assert_eq!(bound("(a (b c) & rest)", nested, "rest"), "(4 5)");
+
+        assert_eq!(bound("(a & rest :as all)", "(list 1)", "rest"), "nil");
+        assert_eq!(bound("(a & rest :as all)", "(list 1)", "all"), "(1)");
+    }
+
+    #[test]
+    fn lazy_seq_destructuring() {
+        let mut env = Scope::new();
+
+        assert_eq!(bound("(a b)", "(map inc (list 1 2))", "a"), "2");
+        assert_eq!(bound("(a b)", "(map inc (list 1 2))", "b"), "3");
+        assert_eq!(bound("(a (b c))", "(list 1 (range 2 4))", "c"), "3");
+
+        // only as much as the pattern needs is realized, so the rest of an infinite seq stays lazy
+        assert_eq!(bound("(a b & more)", "(range)", "b"), "1");
+        assert_eq!(bound("(a b & more)", "(range)", "(take 2 more)"), "(2 3)");
+        assert_eq!(bound("(a & more)", "(range 1)", "more"), "nil");
+        assert_eq!(bound("(a & more :as all)", "(range 2)", "all"), "(0 1)");
+
+        assert_eq!(eval("((lambda ((x y) z) (+ x y z)) (map inc (list 1 2)) 3)", &mut env).to_string(), "8");
+        eval("(defn firsts ((a b & _)) (list a b))", &mut env);
+        assert_eq!(eval("(firsts (iterate inc 5))", &mut env).to_string(), "(5 6)");
+
+        match eval("(let ((a b) (range 1)) a)", &mut env) {
+            LispValue::Error(e) => assert_eq!(e, "expected 2 values to destructure but got 1"),
+            other => panic!("expected an error, got {}", other)
+        }
+
+        match eval("(let ((a b) (range)) a)", &mut env) {
+            LispValue::Error(e) => assert_eq!(e, "expected 2 values to destructure but got more"),
+            other => panic!("expected an error, got {}", other)
+        }
+
+        assert!(matches!(eval("(let ((a) (map (lambda (x) (/ 1 x)) (list 0))) a)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn rest_parameters() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("((lambda (x & more) more) 1 2 3)", &mut env).to_string(), "(2 3)");
+        // recur passes the rest parameter as one list
+        assert_eq!(eval("((lambda (n & acc) (if (zero? n) acc (recur (dec n) (cons n (or acc (list)))))) 3)", &mut env).to_string(),
+                   "(1 2 3)");
+    }
+
+    #[test]
+    fn map_destructuring() {
+        assert_eq!(bound("{a :a b \"b\"}", "{:a 1 \"b\" 2}", "b"), "2");
+        assert_eq!(bound("{:keys (x y) :or {y 10} :as m}", "{:x 1}", "x"), "1");
+        assert_eq!(bound("{:keys (x y) :or {y 10} :as m}", "{:x 1}", "y"), "10");
+        assert_eq!(bound("{:keys (x y) :or {y 10} :as m}", "{:x 1}", "m"), "{:x 1}");
+
+        assert_eq!(eval("((lambda ({:keys (a)} (b)) (+ a b)) {:a 1} (list 2))", &mut Scope::new()).to_string(), "3");
+    }
+
+    #[test]
+    fn shape_mismatches_are_errors() {
+        let mut env = Scope::new();
+
+        match eval("(let ((a b) (list 1)) a)", &mut env) {
+            LispValue::Error(e) => assert_eq!(e, "expected 2 values to destructure but got 1"),
+            other => panic!("expected an error, got {}", other)
+        }
+
+        assert!(matches!(eval("(let ((a) 1) a)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(let ({a :a} (list 1)) a)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("((lambda (a) a) 1 2)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(lambda (a & b c) a)", &mut env), LispValue::Error(_)));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::types::ast::{LispValue};
 use crate::types::list::{List};
-use crate::types::unit::Unit;
-use crate::reader::tokenizer::TokenType;
+use crate::types::map::Map;
+use crate::types::set::Set;
+use crate::types::symbol::Symbol;
 use crate::types::env::Scope;
+use crate::types::function::Function;
 
 #[macro_export]
 macro_rules! arg_return {
@@ -14,113 +16,153 @@ macro_rules! arg_return {
 }
 
 
-pub fn eval_ast(root: &LispValue, mut env: &mut Scope) -> LispValue {
+pub fn eval_ast(root: &LispValue, env: &mut Scope) -> LispValue {
 
     // handle macro expansion
     if let LispValue::List(list) = root {
-        if let Some(first_token) = list.first_token() {
-            if let Some(LispValue::Macro(l)) = env.get(first_token.get_text()) {
-                let evaluated_macro = l(list, env);
+        if let Some(name) = list.first_symbol() {
+            if let Some(LispValue::Macro(l)) = env.get(name) {
+                let evaluated_macro = l.call(list, env);
                 return eval_ast(&evaluated_macro, env)
             }
         }
     }
 
     match root {
-        LispValue::List(list) => eval_list(&list, &mut env),
-        LispValue::Unit(atom) => eval_symbol(&atom, env),
+        LispValue::List(list) => eval_list(list, env),
+        LispValue::Symbol(atom) => eval_symbol(atom, env),
+        LispValue::Map(map) => eval_map(map, env),
+        LispValue::Set(set) => eval_set(set, env),
         _ => root.clone()
     }
 }
 
-pub fn eval_list(list: &List, env: &mut Scope) -> LispValue {
+/// evaluates every argument after the operator, stopping at the first error
+pub fn eval_args(list: &List, env: &mut Scope) -> Result<Vec<LispValue>, LispValue> {
+    let mut args = Vec::with_capacity(list.len());
 
-    if list.len() == 0 {
-        return LispValue::Nil
+    for item in list.iter().skip(1) {
+        match eval_ast(item, env) {
+            LispValue::Error(e) => return Err(LispValue::Error(e)),
+            value => args.push(value)
+        }
     }
 
-    let op = eval_ast(&list[0], env);
+    Ok(args)
+}
 
-    match op {
-        LispValue::Function(f) => f(list, env),
-        LispValue::Error(s) => LispValue::Error(s),
-        _ => LispValue::Error(format!("cannot evaluate list: {}", list).to_string())
+/// calls f with arguments that are already evaluated. functions evaluate their argument forms,
+/// so anything that doesn't evaluate to itself is quoted first.
+pub fn call_function(f: &Function, args: Vec<LispValue>, env: &mut Scope) -> LispValue {
+    let mut forms = vec![LispValue::Nil];
+
+    forms.extend(args.into_iter().map(|arg| match arg {
+        LispValue::List(_) | LispValue::Symbol(_) | LispValue::Map(_) | LispValue::Set(_) => {
+            LispValue::List(List::from_vec(vec![LispValue::Symbol(Symbol::new("quote")), arg]))
+        },
+        arg => arg
+    }));
+
+    f.call(&List::from_vec(forms), env)
+}
+
+pub fn eval_map(map: &Map, env: &mut Scope) -> LispValue {
+    let mut evaluated = Map::new();
+
+    for (key, value) in map.iter() {
+        let key = eval_ast(key, env);
+        let value = eval_ast(value, env);
+
+        match (key, value) {
+            (LispValue::Error(e), _) | (_, LispValue::Error(e)) => return LispValue::Error(e),
+            (key, value) => evaluated.insert(key, value)
+        }
     }
+
+    LispValue::Map(evaluated)
 }
 
-fn convert_string(s: &String) -> LispValue {
-    if !s.starts_with("\"")|| !s.ends_with("\"") {
-        LispValue::Error("malformatted string".to_string())
-    } else {
-        let mut copy = s.replace("\\n", "\n");
-        copy.pop();
-        copy.remove(0);
+pub fn eval_set(set: &Set, env: &mut Scope) -> LispValue {
+    let mut evaluated = Set::new();
 
-        LispValue::String(copy)
+    for item in set.iter() {
+        match eval_ast(item, env) {
+            LispValue::Error(e) => return LispValue::Error(e),
+            value => evaluated.insert(value)
+        };
     }
+
+    LispValue::Set(evaluated)
 }
 
-pub fn eval_symbol(atom: &Unit, env: &mut Scope) -> LispValue {
-    if atom.token().get_type() == TokenType::String {
-        return convert_string(atom.token().get_text())
+pub fn eval_list(list: &List, env: &mut Scope) -> LispValue {
+
+    if list.is_empty() {
+        return LispValue::Nil
     }
 
-    let string = atom.token().get_text();
+    let op = eval_ast(&list[0], env);
+
+    match op {
+        LispValue::Function(f) => f.call(list, env),
+        LispValue::Error(s) => LispValue::Error(s),
+        _ => LispValue::Error(format!("cannot evaluate list: {}", list).to_string())
+    }
+}
+
+pub fn eval_symbol(atom: &Symbol, env: &mut Scope) -> LispValue {
+    let string = atom.name();
     // check if this symbol is defined
     // note that this means our language currently allows for redefinitions
     if let Some(result) = env.get(string) {
         return result
     }
 
-    // in order try nil, true, false and finally check for floats and ints
-    match string.as_str() {
+    // numbers are already parsed by the reader, so all that is left is nil, true and false
+    match string {
         "nil" => LispValue::Nil,
         "true" => LispValue::Boolean(true),
         "false" => LispValue::Boolean(false),
-        _ => {
-            match string.parse::<i64>() {
-                Ok(i) => LispValue::Int(i),
-
-                _ => match string.parse::<f64>() {
-                    Ok(f) => LispValue::Float(f),
-                    _ => LispValue::Error(format!("could not parse symbol: {}", string))
-                }
-            }
-        }
+        _ => LispValue::Error(format!("symbol not found: {}", string))
     }
 }
 
 
 #[cfg(test)]
+#[allow(clippy::approx_constant, clippy::assertions_on_constants)]
 mod test {
-    use super::{eval_symbol, LispValue};
-    use crate::reader::tokenizer::{Token, TokenType};
-    use crate::types::atom::Unit;
+    use super::{eval_symbol, eval_ast, LispValue};
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::types::symbol::Symbol;
     use crate::types::env::Scope;
 
     #[test]
     fn test_ints_and_floats() {
         let mut env = Scope::new();
+        let tokenizer = Tokenizer::new();
 
-        let test_token_float = Token::new("3.14".to_string(), TokenType::Symbol);
-        let test_token_int = Token::new("3".to_string(), TokenType::Symbol);
-        let test_token_gibberish = Token::new("1984.38471jf".to_string(), TokenType::Symbol);
-
-        let lisp_value_float =Unit::new(test_token_float);
-        let lisp_value_int = Unit::new(test_token_int);
-        let lisp_val_bad = Unit::new(test_token_gibberish);
+        // numbers are parsed by the reader, a symbol never turns into one
+        let lisp_value_float = read_string(&tokenizer, "3.14".to_string());
+        let lisp_value_int = read_string(&tokenizer, "3".to_string());
+        let lisp_val_bad = read_string(&tokenizer, "1984.38471jf".to_string());
 
-        match eval_symbol(&lisp_value_float, &mut env) {
+        match eval_ast(&lisp_value_float, &mut env) {
             LispValue::Float(v) => assert_eq!(v, 3.14),
             _ => assert!(false)
         }
 
-        match eval_symbol(&lisp_value_int, &mut env) {
+        match eval_ast(&lisp_value_int, &mut env) {
             LispValue::Int(v) => assert_eq!(v, 3),
             _ => assert!(false)
         }
 
-        match eval_symbol(&(lisp_val_bad), &mut env) {
+        match eval_ast(&(lisp_val_bad), &mut env) {
+            LispValue::Error(_) => assert!(true),
+            _ => assert!(false)
+        }
+
+        match eval_symbol(&Symbol::new("3.14"), &mut env) {
             LispValue::Error(_) => assert!(true),
             _ => assert!(false)
         }
@@ -131,8 +173,8 @@ mod test {
     #[test]
     fn true_false() {
         let mut env = Scope::new();
-        let test_token_false = Unit::new(Token::new("false".to_string(), TokenType::Symbol));
-        let test_token_true = Unit::new(Token::new("true".to_string(), TokenType::Symbol));
+        let test_token_false = Symbol::new("false");
+        let test_token_true = Symbol::new("true");
 
         match eval_symbol(&(test_token_false), &mut env) {
             LispValue::Boolean(v) => assert!(!v),
@@ -149,7 +191,7 @@ mod test {
     fn test_nil() {
         let mut env = Scope::new();
 
-        let test_nil_token = Unit::new(Token::new("nil".to_string(), TokenType::Symbol));
+        let test_nil_token = Symbol::new("nil");
 
         match eval_symbol(&test_nil_token, &mut env) {
             LispValue::Nil => assert!(true),
@@ -1,51 +1,81 @@
 
 use std::collections::VecDeque;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, Pow, Signed, Zero};
+use std::cmp::Ordering;
 use crate::types::list::List;
 use crate::types::ast::LispValue;
 use crate::exec::eval::{eval_ast};
 use crate::types::env::Scope;
+use crate::types::numeric::{normalize, normalize_ratio, to_float, to_ratio, numeric_ordering};
+use crate::arg_return;
 
+/// name of the root binding that decides what happens when int math overflows,
+/// true promotes to a big int, false returns an error.
+pub const AUTO_PROMOTE: &str = "*auto-promote*";
 
-macro_rules! operate {
+// once both sides are exact but not plain ints we do the operation on ratios,
+// anything mixed with a float becomes a float.
+macro_rules! exact_or_float {
     ($op:tt, $a:expr, $b:expr) => {
-        match $a {
-            LispValue::Error(message) => LispValue::Error(message),
-
-            LispValue::Int(i) => match $b {
-                LispValue::Error(message) => LispValue::Error(message),
-                LispValue::Int(i2) => LispValue::Int(i $op i2),
-                LispValue::Float(f2) => LispValue::Float(i as f64 $op f2),
+        match (to_ratio(&$a), to_ratio(&$b)) {
+            (Some(r), Some(r2)) => normalize_ratio(r $op r2),
+            _ => match (to_float(&$a), to_float(&$b)) {
+                (Some(f), Some(f2)) => LispValue::Float(f $op f2),
                 _ => LispValue::Error("incompatible types".to_string())
-            },
+            }
+        }
+    }
+}
 
-            LispValue::Float(f) => match $b {
-                LispValue::Error(message) => LispValue::Error(message),
-                LispValue::Int(i2) => LispValue::Float(f $op i2 as f64),
-                LispValue::Float(f2) => LispValue::Float(f $op f2),
-                 _ => LispValue::Error("incompatible types".to_string())
+// ints use the checked version of the operator and fall back to big ints when it overflows.
+macro_rules! operate {
+    ($op:tt, $checked:ident, $a:expr, $b:expr) => {
+        match ($a, $b) {
+            (LispValue::Error(message), _) | (_, LispValue::Error(message)) => LispValue::Error(message),
 
+            (LispValue::Int(i), LispValue::Int(i2)) => match i.$checked(i2) {
+                Some(result) => LispValue::Int(result),
+                None => normalize(BigInt::from(i) $op BigInt::from(i2))
             },
+            (LispValue::Int(i), LispValue::BigInt(b2)) => normalize(BigInt::from(i) $op b2),
+            (LispValue::BigInt(b), LispValue::Int(i2)) => normalize(b $op BigInt::from(i2)),
+            (LispValue::BigInt(b), LispValue::BigInt(b2)) => normalize(b $op b2),
 
-            _ => LispValue::Error("incompatible types".to_string())
-
+            (a, b) => exact_or_float!($op, a, b)
         }
     };
 }
 
-fn add_helper(a: LispValue, b: LispValue) -> LispValue {
-    operate!(+, a, b)
+pub fn add_helper(a: LispValue, b: LispValue) -> LispValue {
+    operate!(+, checked_add, a, b)
 }
 
 fn sub_helper(a: LispValue, b: LispValue) -> LispValue {
-    operate!(-, a, b)
+    operate!(-, checked_sub, a, b)
 }
 
 fn mul_helper(a: LispValue, b: LispValue) -> LispValue {
-    operate!(*, a, b)
+    operate!(*, checked_mul, a, b)
 }
 
+/// dividing exact numbers stays exact, ints that don't divide evenly give a ratio
 fn div_helper(a: LispValue, b: LispValue) -> LispValue {
-    operate!(/, a, b)
+    match (a, b) {
+        (LispValue::Error(message), _) | (_, LispValue::Error(message)) => LispValue::Error(message),
+
+        // big ints and ratios are always normalized, so only a plain int can be zero
+        (LispValue::Int(_), LispValue::Int(0))
+        | (LispValue::BigInt(_), LispValue::Int(0))
+        | (LispValue::Ratio(_), LispValue::Int(0)) => {
+            LispValue::Error("division by zero".to_string())
+        },
+
+        (LispValue::Int(i), LispValue::Int(i2)) if i.checked_rem(i2) == Some(0) => LispValue::Int(i / i2),
+
+        (a, b) => exact_or_float!(/, a, b)
+    }
 }
 
 macro_rules! gen_reducer {
@@ -64,31 +94,491 @@ macro_rules! gen_reducer {
 }
 
 fn prepare_args(args: &List, env: &mut Scope) -> VecDeque<LispValue> {
-    args.items()
-        .iter()
+    args.iter()
         .skip(1)
         .map(|x| eval_ast(x, env))
         .collect()
 }
 
+fn auto_promote(env: &Scope) -> bool {
+    !matches!(env.get(AUTO_PROMOTE), Some(LispValue::Boolean(false)) | Some(LispValue::Nil))
+}
+
+/// the overflow policy is only looked up when a big int comes out of arguments that were all small,
+/// so the common case doesn't pay for the extra environment lookup.
+fn check_overflow(result: LispValue, had_big: bool, env: &Scope) -> LispValue {
+    if let LispValue::BigInt(_) = result {
+        if !had_big && !auto_promote(env) {
+            return LispValue::Error("integer overflow".to_string())
+        }
+    }
+
+    result
+}
+
+fn reduce_checked(operator: fn(LispValue, LispValue) -> LispValue, mut mapped: VecDeque<LispValue>, env: &Scope) -> LispValue {
+    let had_big = mapped.iter().any(|value| matches!(value, LispValue::BigInt(_)));
+
+    let result = gen_reducer!(operator, mapped);
+
+    check_overflow(result, had_big, env)
+}
+
+/// (+) is 0, the sum of nothing
+pub fn add (args: &List, env: &mut Scope) -> LispValue {
+    let mapped = prepare_args(args, env);
 
+    if mapped.is_empty() {
+        return LispValue::Int(0)
+    }
 
-pub fn add (args: &List, mut env: &mut Scope) -> LispValue {
-    let mut mapped = prepare_args(args, &mut env);
-    gen_reducer!(add_helper, mapped)
+    reduce_checked(add_helper, mapped, env)
 }
 
+/// (- x) negates x by subtracting it from 0, so negating the smallest int promotes too
 pub fn sub (args: &List, env: &mut Scope) -> LispValue {
     let mut mapped = prepare_args(args, env);
-    gen_reducer!(sub_helper, mapped)
+
+    if mapped.len() == 1 {
+        mapped.push_front(LispValue::Int(0));
+    }
+
+    reduce_checked(sub_helper, mapped, env)
 }
 
+/// (*) is 1, the product of nothing
 pub fn mul (args: &List, env: &mut Scope) -> LispValue {
-    let mut mapped = prepare_args(args, env);
-    gen_reducer!(mul_helper, mapped)
+    let mapped = prepare_args(args, env);
+
+    if mapped.is_empty() {
+        return LispValue::Int(1)
+    }
+
+    reduce_checked(mul_helper, mapped, env)
 }
 
+/// (/ x) is the reciprocal of x, 1 divided by it
 pub fn div (args: &List, env: &mut Scope) -> LispValue {
     let mut mapped = prepare_args(args, env);
-    gen_reducer!(div_helper, mapped)
+
+    if mapped.len() == 1 {
+        mapped.push_front(LispValue::Int(1));
+    }
+
+    reduce_checked(div_helper, mapped, env)
+}
+
+fn eval_number(list: &List, env: &mut Scope) -> Result<LispValue, LispValue> {
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => Err(LispValue::Error(e)),
+        value if to_float(&value).is_some() => Ok(value),
+        _ => Err(LispValue::Error("expected a number".to_string()))
+    }
+}
+
+pub fn apply_numerator(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(numerator, 1, list);
+
+    match eval_number(list, env) {
+        Err(e) => e,
+        Ok(value) => match to_ratio(&value) {
+            Some(r) => normalize(r.numer().clone()),
+            None => LispValue::Error("numerator needs a rational number".to_string())
+        }
+    }
+}
+
+pub fn apply_denominator(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(denominator, 1, list);
+
+    match eval_number(list, env) {
+        Err(e) => e,
+        Ok(value) => match to_ratio(&value) {
+            Some(r) => normalize(r.denom().clone()),
+            None => LispValue::Error("denominator needs a rational number".to_string())
+        }
+    }
+}
+
+/// ints are rationals too, only floats aren't
+pub fn apply_is_rational(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(is_rational, 1, list);
+
+    match eval_ast(&list[1], env) {
+        LispValue::Error(e) => LispValue::Error(e),
+        value => LispValue::Boolean(to_ratio(&value).is_some())
+    }
+}
+
+// integer division family: quot truncates towards zero, rem takes the sign of the dividend
+// and mod takes the sign of the divisor.
+#[derive(Clone, Copy)]
+enum Division {
+    Quot,
+    Rem,
+    Mod
+}
+
+fn divide_exact(a: BigRational, b: BigRational, division: Division) -> LispValue {
+    let quot = (&a / &b).trunc();
+    let rem = &a - &b * &quot;
+
+    match division {
+        Division::Quot => normalize_ratio(quot),
+        Division::Rem => normalize_ratio(rem),
+        Division::Mod => {
+            if !rem.is_zero() && rem.is_negative() != b.is_negative() {
+                normalize_ratio(rem + b)
+            } else {
+                normalize_ratio(rem)
+            }
+        }
+    }
+}
+
+fn divide_float(a: f64, b: f64, division: Division) -> LispValue {
+    let rem = a % b;
+
+    match division {
+        Division::Quot => LispValue::Float((a / b).trunc()),
+        Division::Rem => LispValue::Float(rem),
+        Division::Mod => {
+            if rem != 0.0 && (rem < 0.0) != (b < 0.0) {
+                LispValue::Float(rem + b)
+            } else {
+                LispValue::Float(rem)
+            }
+        }
+    }
+}
+
+fn integer_division(list: &List, env: &mut Scope, division: Division) -> LispValue {
+    if list.len() != 3 {
+        return LispValue::Error("quot, rem and mod take 2 args".to_string())
+    }
+
+    let a = eval_ast(&list[1], env);
+    let b = eval_ast(&list[2], env);
+
+    match (a, b) {
+        (LispValue::Error(message), _) | (_, LispValue::Error(message)) => LispValue::Error(message),
+        (a, LispValue::Int(0)) if to_ratio(&a).is_some() => LispValue::Error("division by zero".to_string()),
+
+        // i64::MIN / -1 is the only case where these overflow, it falls through to the exact path
+        (LispValue::Int(i), LispValue::Int(i2)) if i2 != -1 => {
+            let rem = i % i2;
+
+            match division {
+                Division::Quot => LispValue::Int(i / i2),
+                Division::Rem => LispValue::Int(rem),
+                Division::Mod if rem != 0 && (rem < 0) != (i2 < 0) => LispValue::Int(rem + i2),
+                Division::Mod => LispValue::Int(rem)
+            }
+        },
+
+        (a, b) => match (to_ratio(&a), to_ratio(&b)) {
+            (Some(r), Some(r2)) => divide_exact(r, r2, division),
+            _ => match (to_float(&a), to_float(&b)) {
+                (Some(f), Some(f2)) => divide_float(f, f2, division),
+                _ => LispValue::Error("expected a number".to_string())
+            }
+        }
+    }
+}
+
+pub fn apply_quot(list: &List, env: &mut Scope) -> LispValue {
+    integer_division(list, env, Division::Quot)
+}
+
+pub fn apply_rem(list: &List, env: &mut Scope) -> LispValue {
+    integer_division(list, env, Division::Rem)
+}
+
+pub fn apply_mod(list: &List, env: &mut Scope) -> LispValue {
+    integer_division(list, env, Division::Mod)
+}
+
+pub fn apply_abs(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(abs, 1, list);
+
+    match eval_number(list, env) {
+        Err(e) => e,
+        Ok(LispValue::Int(i)) => match i.checked_abs() {
+            Some(result) => LispValue::Int(result),
+            None => check_overflow(normalize(BigInt::from(i).abs()), false, env)
+        },
+        Ok(LispValue::BigInt(b)) => LispValue::BigInt(b.abs()),
+        Ok(LispValue::Ratio(r)) => LispValue::Ratio(r.abs()),
+        Ok(LispValue::Float(f)) => LispValue::Float(f.abs()),
+        Ok(_) => LispValue::Error("expected a number".to_string())
+    }
+}
+
+// min and max hand back the winning argument untouched, so (max 1 2.0) is 2.0 and (max 2 1.0) is 2
+fn pick(list: &List, env: &mut Scope, keep: Ordering) -> LispValue {
+    let mut args = prepare_args(list, env);
+    let mut best = match args.pop_front() {
+        Some(first) => first,
+        None => return LispValue::Error("function called with no arguments".to_string())
+    };
+
+    for next in args.into_iter() {
+        if let LispValue::Error(e) = next {
+            return LispValue::Error(e)
+        }
+
+        match numeric_ordering(&next, &best) {
+            Some(ordering) if ordering == keep => best = next,
+            Some(_) => (),
+            None => return LispValue::Error("expected a number".to_string())
+        }
+    }
+
+    best
+}
+
+pub fn apply_min(list: &List, env: &mut Scope) -> LispValue {
+    pick(list, env, Ordering::Less)
+}
+
+pub fn apply_max(list: &List, env: &mut Scope) -> LispValue {
+    pick(list, env, Ordering::Greater)
+}
+
+/// exact bases raised to an int power stay exact (negative powers give ratios),
+/// everything else is done with floats.
+pub fn apply_pow(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(pow, 2, list);
+
+    let base = eval_ast(&list[1], env);
+    let exponent = eval_ast(&list[2], env);
+
+    match (base, exponent) {
+        (LispValue::Error(message), _) | (_, LispValue::Error(message)) => LispValue::Error(message),
+
+        (LispValue::Int(b), LispValue::Int(e)) if e >= 0 && e <= u32::MAX as i64 => {
+            match b.checked_pow(e as u32) {
+                Some(result) => LispValue::Int(result),
+                None => check_overflow(normalize(BigInt::from(b).pow(e as u32)), false, env)
+            }
+        },
+
+        (base, LispValue::Int(e)) if to_ratio(&base).is_some() => {
+            let r = to_ratio(&base).unwrap();
+
+            if e < 0 && r.is_zero() {
+                LispValue::Error("division by zero".to_string())
+            } else if e < i32::MIN as i64 || e > i32::MAX as i64 {
+                LispValue::Error("exponent is too large".to_string())
+            } else {
+                normalize_ratio(r.pow(e as i32))
+            }
+        },
+
+        (base, exponent) => match (to_float(&base), to_float(&exponent)) {
+            (Some(b), Some(e)) => LispValue::Float(b.powf(e)),
+            _ => LispValue::Error("expected a number".to_string())
+        }
+    }
+}
+
+// these always produce floats, (sqrt 4) is 2.0
+macro_rules! float_op {
+    ($func:ident, $name:ident, $method:ident) => {
+        pub fn $func(list: &List, env: &mut Scope) -> LispValue {
+            arg_return!($name, 1, list);
+
+            match eval_number(list, env).map(|value| to_float(&value)) {
+                Err(e) => e,
+                Ok(Some(f)) => LispValue::Float(f.$method()),
+                Ok(None) => LispValue::Error("expected a number".to_string())
+            }
+        }
+    }
+}
+
+float_op!(apply_sqrt, sqrt, sqrt);
+float_op!(apply_exp, exp, exp);
+float_op!(apply_log, log, ln);
+float_op!(apply_sin, sin, sin);
+float_op!(apply_cos, cos, cos);
+float_op!(apply_tan, tan, tan);
+
+// rounding always gives a whole number, an int or a big int: floats and ratios are rounded
+// and ints are already whole. round takes halves away from zero, so -2.5 becomes -3 and 5/2 3.
+macro_rules! round_op {
+    ($func:ident, $name:ident, $method:ident) => {
+        pub fn $func(list: &List, env: &mut Scope) -> LispValue {
+            arg_return!($name, 1, list);
+
+            match eval_number(list, env) {
+                Err(e) => e,
+                Ok(LispValue::Float(f)) => match BigInt::from_f64(f.$method()) {
+                    Some(whole) => normalize(whole),
+                    None => LispValue::Error(format!("cannot {} {}", stringify!($name), f))
+                },
+                Ok(LispValue::Ratio(r)) => normalize_ratio(r.$method()),
+                Ok(value) => value
+            }
+        }
+    }
+}
+
+round_op!(apply_floor, floor, floor);
+round_op!(apply_ceil, ceil, ceil);
+round_op!(apply_round, round, round);
+
+fn step(list: &List, env: &mut Scope, by: i64) -> LispValue {
+    match eval_number(list, env) {
+        Err(e) => e,
+        Ok(value) => {
+            let had_big = matches!(value, LispValue::BigInt(_));
+            check_overflow(add_helper(value, LispValue::Int(by)), had_big, env)
+        }
+    }
+}
+
+pub fn apply_inc(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(inc, 1, list);
+    step(list, env, 1)
+}
+
+pub fn apply_dec(list: &List, env: &mut Scope) -> LispValue {
+    arg_return!(dec, 1, list);
+    step(list, env, -1)
+}
+
+// the sign predicates and even?/odd? only make sense for numbers, passing anything else is an error
+macro_rules! number_predicate {
+    ($func:ident, $name:ident, $test:expr) => {
+        pub fn $func(list: &List, env: &mut Scope) -> LispValue {
+            arg_return!($name, 1, list);
+
+            let test: fn(&LispValue) -> Option<bool> = $test;
+
+            match eval_number(list, env).map(|value| test(&value)) {
+                Err(e) => e,
+                Ok(Some(b)) => LispValue::Boolean(b),
+                Ok(None) => LispValue::Error(concat!(stringify!($name), " is not defined for this number").to_string())
+            }
+        }
+    }
+}
+
+number_predicate!(apply_is_zero, zero, |v| numeric_ordering(v, &LispValue::Int(0)).map(|o| o == Ordering::Equal));
+number_predicate!(apply_is_pos, pos, |v| numeric_ordering(v, &LispValue::Int(0)).map(|o| o == Ordering::Greater));
+number_predicate!(apply_is_neg, neg, |v| numeric_ordering(v, &LispValue::Int(0)).map(|o| o == Ordering::Less));
+number_predicate!(apply_is_even, even, |v| match v {
+    LispValue::Int(i) => Some(i % 2 == 0),
+    LispValue::BigInt(b) => Some((b % 2u32).is_zero()),
+    _ => None
+});
+number_predicate!(apply_is_odd, odd, |v| match v {
+    LispValue::Int(i) => Some(i % 2 != 0),
+    LispValue::BigInt(b) => Some(!(b % 2u32).is_zero()),
+    _ => None
+});
+
+#[cfg(test)]
+mod test {
+    use crate::types::ast::LispValue;
+    use crate::types::env::Scope;
+    use crate::exec::test_util::eval;
+
+    #[test]
+    fn overflow_promotes_to_big_ints() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(* 9999999999 9999999999)", &mut env).to_string(), "99999999980000000001");
+        assert!(matches!(eval("(- (+ 9223372036854775807 1) 1)", &mut env), LispValue::Int(i64::MAX)));
+
+        eval("(def! *auto-promote* false)", &mut env);
+        assert!(matches!(eval("(* 9999999999 9999999999)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn empty_sums_and_negation() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(+)", &mut env).to_string(), "0");
+        assert_eq!(eval("(*)", &mut env).to_string(), "1");
+        assert_eq!(eval("(- 5)", &mut env).to_string(), "-5");
+        assert_eq!(eval("(- -1/2)", &mut env).to_string(), "1/2");
+        assert_eq!(eval("(- -9223372036854775808)", &mut env).to_string(), "9223372036854775808");
+        assert!(matches!(eval("(-)", &mut env), LispValue::Error(_)));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        let mut env = Scope::new();
+
+        assert!(matches!(eval("(/ 1 0)", &mut env), LispValue::Error(_)));
+        assert_eq!(eval("(try (/ 1 0) (catch e e))", &mut env).to_string(), "division by zero");
+    }
+
+    #[test]
+    fn int_division_is_exact() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(/ 1 3)", &mut env).to_string(), "1/3");
+        assert_eq!(eval("(/ 6 3)", &mut env).to_string(), "2");
+        assert_eq!(eval("(+ 1/3 2/3)", &mut env).to_string(), "1");
+        assert_eq!(eval("(* 1/3 0.5)", &mut env).to_string(), "0.16666666666666666");
+        assert_eq!(eval("(denominator (/ 4 6))", &mut env).to_string(), "3");
+        assert!(matches!(eval("(< 1/3 0.5)", &mut env), LispValue::Boolean(true)));
+    }
+
+    #[test]
+    fn division_family_signs() {
+        let mut env = Scope::new();
+
+        // quot and rem truncate towards zero, mod takes the sign of the divisor
+        assert_eq!(eval("(quot -7 2)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(rem -7 2)", &mut env).to_string(), "-1");
+        assert_eq!(eval("(mod -7 2)", &mut env).to_string(), "1");
+        assert_eq!(eval("(mod 7 -2)", &mut env).to_string(), "-1");
+        assert_eq!(eval("(mod -7.5 2)", &mut env).to_string(), "0.5");
+        assert_eq!(eval("(mod 7/2 1)", &mut env).to_string(), "1/2");
+        assert!(matches!(eval("(mod 1 0)", &mut env), LispValue::Error(_)));
+
+        // a single argument is divided into 1
+        assert_eq!(eval("(/ 2)", &mut env).to_string(), "1/2");
+        assert_eq!(eval("(/ -1/3)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(/ 4.0)", &mut env).to_string(), "0.25");
+        assert_eq!(eval("(try (/ 0) (catch e e))", &mut env).to_string(), "division by zero");
+    }
+
+    #[test]
+    fn library_functions_keep_promotion_rules() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(pow 2 100)", &mut env).to_string(), "1267650600228229401496703205376");
+        assert_eq!(eval("(pow 2 -2)", &mut env).to_string(), "1/4");
+        assert_eq!(eval("(pow 4 0.5)", &mut env).to_string(), "2.0");
+        assert_eq!(eval("(inc 9223372036854775807)", &mut env).to_string(), "9223372036854775808");
+        assert_eq!(eval("(abs -9223372036854775808)", &mut env).to_string(), "9223372036854775808");
+        assert_eq!(eval("(max 1 2.0 3/2)", &mut env).to_string(), "2.0");
+        assert_eq!(eval("(floor 7/2)", &mut env).to_string(), "3");
+        assert!(matches!(eval("(even? 1.5)", &mut env), LispValue::Error(_)));
+        assert!(matches!(eval("(number? 1/2)", &mut env), LispValue::Boolean(true)));
+    }
+
+    #[test]
+    fn rounding_gives_whole_numbers() {
+        let mut env = Scope::new();
+
+        assert_eq!(eval("(round 2.5)", &mut env).to_string(), "3");
+        assert_eq!(eval("(round -2.5)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(round -2.4)", &mut env).to_string(), "-2");
+        assert_eq!(eval("(round 5/2)", &mut env).to_string(), "3");
+        assert_eq!(eval("(round -5/2)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(round -7/3)", &mut env).to_string(), "-2");
+        assert_eq!(eval("(round 7)", &mut env).to_string(), "7");
+        assert_eq!(eval("(floor -2.5)", &mut env).to_string(), "-3");
+        assert_eq!(eval("(ceil -2.5)", &mut env).to_string(), "-2");
+        assert_eq!(eval("(floor -7/2)", &mut env).to_string(), "-4");
+        assert_eq!(eval("(round 1e20)", &mut env).to_string(), "100000000000000000000");
+        assert!(matches!(eval("(int? (round 2.5))", &mut env), LispValue::Boolean(true)));
+        assert!(matches!(eval("(round (/ 0.0 0.0))", &mut env), LispValue::Error(_)));
+    }
 }
@@ -1,11 +1,26 @@
 pub mod eval;
 pub mod math;
 pub mod core_atom;
+pub mod core_char;
 pub mod core_comparison;
+pub mod core_doc;
 pub mod core_file;
+pub mod core_lazy;
 pub mod core_list;
+pub mod core_map;
 pub mod core_meta;
+pub mod core_multi;
+pub mod core_ns;
+pub mod core_record;
 pub mod core_recursive;
+pub mod core_regex;
+pub mod core_set;
+pub mod core_symbol;
+pub mod core_type;
 pub mod core_utils;
 pub mod closure;
+pub mod destructure;
 
+
+#[cfg(test)]
+pub mod test_util;
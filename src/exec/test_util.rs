@@ -0,0 +1,10 @@
+use crate::types::ast::LispValue;
+use crate::types::env::Scope;
+use crate::reader::tokenizer::Tokenizer;
+use crate::exec::core_utils::read_string;
+use crate::exec::eval::eval_ast;
+
+/// reads code and evaluates it in env, what the tests of the builtins run their lisp through
+pub fn eval(code: &str, env: &mut Scope) -> LispValue {
+    eval_ast(&read_string(&Tokenizer::new(), code.to_string()), env)
+}
@@ -19,7 +19,7 @@ impl UserIO {
         let mut input = String::new();
 
         let bytes = io::stdin().read_line(&mut input)
-            .ok().expect("could not read line");
+            .expect("could not read line");
 
         if bytes == 0 {
             return None
@@ -39,15 +39,17 @@ impl UserIO {
         print!("{}", line);
         // we need to flush since we are not printing a new line,
         // rust buffers std::out output and only flushes on newlines from my understanding
-        match io::stdout().flush() {
-            _ => ()
-        }
+        let _ = io::stdout().flush();
     }
 
     pub fn greet(&self) {
         self.write(&self.prefix)
     }
 
+}
 
-
+impl Default for UserIO {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
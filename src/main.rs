@@ -3,17 +3,18 @@ use lispinrust::reader::tokenizer::{Tokenizer};
 use lispinrust::exec::eval::eval_ast;
 use lispinrust::types::env::Scope;
 use lispinrust::exec::core_utils::read_string;
+use lispinrust::types::printer::pr_str;
 
 /// pre_load is for functions we want the user to have,
 /// but also defined within lisp and not on the core, interpreter level
-fn pre_load(tokenizer: &Tokenizer, mut env: &mut Scope) {
-    let read_file = read_string(&tokenizer, "(def! load-file (lambda (f) (eval (read-string (str \"(do \" (slurp f) \"\nnil)\")))))".to_string());
-    eval_ast(&read_file, &mut env);
+fn pre_load(tokenizer: &Tokenizer, env: &mut Scope) {
+    let read_file = read_string(tokenizer, "(def! load-file \"(load-file f) reads and evaluates every form in the file f\" (lambda (f) (eval (read-string (str \"(do \" (slurp f) \"\nnil)\")))))".to_string());
+    eval_ast(&read_file, env);
 }
 
 fn main() {
 
-    let cmd = UserIO::new();
+    let mut cmd = UserIO::new();
     let tokenizer = Tokenizer::new();
     let mut maybe_line;
     let mut env = Scope::new();
@@ -22,6 +23,8 @@ fn main() {
     pre_load(&tokenizer, &mut env);
 
     loop {
+        // the prompt names the current namespace, like user>
+        cmd.set_prefix(format!("{}> ", env.namespace_name()));
         cmd.greet();
 
 
@@ -35,12 +38,12 @@ fn main() {
         let user_input = maybe_line.unwrap();
 
         // user just hit enter
-        if user_input.len() == 0 {
+        if user_input.is_empty() {
             continue;
         }
 
         let result = read_string(&tokenizer, user_input);
-        println!("{}", eval_ast(&result, &mut env))
+        println!("{}", pr_str(&eval_ast(&result, &mut env), true))
 
     }
 
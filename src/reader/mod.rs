@@ -1,3 +1,4 @@
+pub mod number;
 pub mod parser;
 pub mod tokenizer;
 use super::error;
\ No newline at end of file
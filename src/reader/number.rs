@@ -0,0 +1,228 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{ToPrimitive, Zero};
+
+/// Number is what a numeric literal decodes to, the reader turns it into the matching LispValue
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Int(i64),
+    // literals that don't fit in an i64
+    BigInt(BigInt),
+    // a/b literals, already reduced so they never have a denominator of one
+    Ratio(BigRational),
+    Float(f64)
+}
+
+fn from_big(big: BigInt) -> Number {
+    match big.to_i64() {
+        Some(i) => Number::Int(i),
+        None => Number::BigInt(big)
+    }
+}
+
+/// anything starting with a digit, or a sign followed by a digit, has to be a valid number.
+/// this keeps spellings like inf or NaN as plain symbols.
+pub fn is_numeric(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_digit() => true,
+        Some('+') | Some('-') => chars.next().is_some_and(|c| c.is_ascii_digit()),
+        _ => false
+    }
+}
+
+/// underscores are only allowed between two digits, e.g. 1_000_000
+fn strip_underscores(digits: &str, radix: u32) -> Result<String, String> {
+    let chars: Vec<char> = digits.chars().collect();
+
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' {
+            let before = i > 0 && chars[i - 1].is_digit(radix);
+            let after = i + 1 < chars.len() && chars[i + 1].is_digit(radix);
+
+            if !before || !after {
+                return Err(format!("misplaced underscore in number {}", digits))
+            }
+        }
+    }
+
+    Ok(digits.replace('_', ""))
+}
+
+/// ratios are written as an optionally signed decimal numerator over an unsigned decimal denominator
+fn parse_ratio(text: &str, numerator: &str, denominator: &str) -> Result<Number, String> {
+    let invalid = || format!("invalid ratio literal {}", text);
+
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let unsigned_numerator = numerator.trim_start_matches(['-', '+']);
+
+    if numerator.len() - unsigned_numerator.len() > 1 || !is_digits(unsigned_numerator) || !is_digits(denominator) {
+        return Err(invalid())
+    }
+
+    let n = numerator.parse::<BigInt>().map_err(|_| invalid())?;
+    let d = denominator.parse::<BigInt>().map_err(|_| invalid())?;
+
+    if d.is_zero() {
+        return Err(format!("ratio with a zero denominator {}", text))
+    }
+
+    let ratio = BigRational::new(n, d);
+
+    if ratio.is_integer() {
+        Ok(from_big(ratio.to_integer()))
+    } else {
+        Ok(Number::Ratio(ratio))
+    }
+}
+
+fn parse_radix(text: &str, digits: &str, radix: u32, negative: bool) -> Result<Number, String> {
+    let digits = strip_underscores(digits, radix)?;
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(format!("invalid number literal {}", text))
+    }
+
+    match BigInt::parse_bytes(digits.as_bytes(), radix) {
+        Some(magnitude) => Ok(from_big(if negative { -magnitude } else { magnitude })),
+        None => Err(format!("invalid number literal {}", text))
+    }
+}
+
+/// decimal literals follow digits ('.' digits*)? ([eE] [+-]? digits)?, they are floats when
+/// either the fraction or the exponent is present.
+fn parse_decimal(text: &str, body: &str, negative: bool) -> Result<Number, String> {
+    let digits = strip_underscores(body, 10)?;
+    let bytes = digits.as_bytes();
+    let invalid = || format!("invalid number literal {}", text);
+
+    let mut i = 0;
+    let mut is_float = false;
+
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+
+    if i < bytes.len() && bytes[i] == b'.' {
+        is_float = true;
+        i += 1;
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        is_float = true;
+        i += 1;
+
+        if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+            i += 1;
+        }
+
+        let exponent_start = i;
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        if i == exponent_start {
+            return Err(invalid())
+        }
+    }
+
+    if i != bytes.len() {
+        return Err(invalid())
+    }
+
+    let signed = if negative { format!("-{}", digits) } else { digits };
+
+    if is_float {
+        signed.parse::<f64>().map(Number::Float).map_err(|_| invalid())
+    } else {
+        signed.parse::<BigInt>().map(from_big).map_err(|_| invalid())
+    }
+}
+
+/// parses a numeric literal: decimal ints and floats with optional exponent, 0x hex, 0o octal and
+/// 0b binary ints, all of them may use underscores as separators. 1/3 style ratios are plain decimals.
+pub fn parse_number(text: &str) -> Result<Number, String> {
+    if let Some(slash) = text.find('/') {
+        return parse_ratio(text, &text[..slash], &text[slash + 1..])
+    }
+
+    let (negative, unsigned) = match text.chars().next() {
+        Some('-') => (true, &text[1..]),
+        Some('+') => (false, &text[1..]),
+        _ => (false, text)
+    };
+
+    let prefix = unsigned.get(..2).map(|p| p.to_ascii_lowercase());
+
+    match prefix.as_deref() {
+        Some("0x") => parse_radix(text, &unsigned[2..], 16, negative),
+        Some("0o") => parse_radix(text, &unsigned[2..], 8, negative),
+        Some("0b") => parse_radix(text, &unsigned[2..], 2, negative),
+        _ => parse_decimal(text, unsigned, negative)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_number, is_numeric, Number};
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    #[test]
+    fn decimal_literals() {
+        assert_eq!(parse_number("42"), Ok(Number::Int(42)));
+        assert_eq!(parse_number("-42"), Ok(Number::Int(-42)));
+        assert_eq!(parse_number("+7"), Ok(Number::Int(7)));
+        assert_eq!(parse_number("1_000_000"), Ok(Number::Int(1000000)));
+        assert_eq!(parse_number("2.5"), Ok(Number::Float(2.5)));
+        assert_eq!(parse_number("1e10"), Ok(Number::Float(1e10)));
+        assert_eq!(parse_number("-1.5E-3"), Ok(Number::Float(-1.5e-3)));
+        assert_eq!(parse_number("-9223372036854775808"), Ok(Number::Int(i64::MIN)));
+        assert_eq!(parse_number("99999999999999999999"),
+                   Ok(Number::BigInt("99999999999999999999".parse::<BigInt>().unwrap())));
+        assert_eq!(parse_number("-0x1_0000_0000_0000_0000"), Ok(Number::BigInt(-(BigInt::from(1) << 64u32))));
+    }
+
+    #[test]
+    fn radix_literals() {
+        assert_eq!(parse_number("0xff"), Ok(Number::Int(255)));
+        assert_eq!(parse_number("-0x10"), Ok(Number::Int(-16)));
+        assert_eq!(parse_number("0b1010_1010"), Ok(Number::Int(170)));
+        assert_eq!(parse_number("0o17"), Ok(Number::Int(15)));
+    }
+
+    #[test]
+    fn ratio_literals() {
+        let third = BigRational::new(BigInt::from(1), BigInt::from(3));
+
+        assert_eq!(parse_number("1/3"), Ok(Number::Ratio(third.clone())));
+        assert_eq!(parse_number("-2/6"), Ok(Number::Ratio(-third)));
+        assert_eq!(parse_number("4/2"), Ok(Number::Int(2)));
+        assert!(parse_number("1/0").is_err());
+        assert!(parse_number("1/-3").is_err());
+        assert!(parse_number("1/3/4").is_err());
+        assert!(parse_number("1.5/2").is_err());
+    }
+
+    #[test]
+    fn rejected_literals() {
+        assert!(parse_number("1984.38471jf").is_err());
+        assert!(parse_number("1_").is_err());
+        assert!(parse_number("1__0").is_err());
+        assert!(parse_number("1e").is_err());
+        assert!(parse_number("0x").is_err());
+        assert!(parse_number("0b102").is_err());
+
+        assert!(!is_numeric("inf"));
+        assert!(!is_numeric("NaN"));
+        assert!(!is_numeric("-"));
+        assert!(!is_numeric("-foo"));
+        assert!(is_numeric("-1x"));
+    }
+}
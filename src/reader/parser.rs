@@ -14,8 +14,12 @@ impl Parser {
     pub fn peek(&self) -> Option<&Token> {
         self.tokens.front()
     }
+}
+
+impl Iterator for Parser {
+    type Item = Token;
 
-    pub fn next(&mut self) -> Option<Token> {
+    fn next(&mut self) -> Option<Token> {
         self.tokens.pop_front()
     }
 }
@@ -2,6 +2,7 @@ use regex::Regex;
 use super::error::{Error, ErrorType}; // instead of super::super we use this here
 use std::fmt;
 use std::collections::VecDeque;
+use super::number::{is_numeric, parse_number};
 
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -9,6 +10,9 @@ pub enum TokenType {
     SpecialTwo,
     SpecialOne,
     String,
+    Regex,
+    Number,
+    Char,
     Comment,
     Symbol
 }
@@ -39,13 +43,15 @@ impl Token {
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         
-        let pre;
-        match self.token_type {
-            TokenType::String => pre = "string",
-            TokenType::SpecialOne => pre = "special",
-            TokenType::SpecialTwo => pre = "special_two",
-            TokenType::Comment => pre = "comment",
-            TokenType::Symbol => pre = "symbol"
+        let pre = match self.token_type {
+            TokenType::String => "string",
+            TokenType::Regex => "regex",
+            TokenType::Number => "number",
+            TokenType::Char => "char",
+            TokenType::SpecialOne => "special",
+            TokenType::SpecialTwo => "special_two",
+            TokenType::Comment => "comment",
+            TokenType::Symbol => "symbol"
         };
 
         write!(f, "{}: {}", pre, self.text)
@@ -61,6 +67,95 @@ pub struct Tokenizer {
 
 pub type ErrorIndex = usize;
 
+/// builds a syntax error pointing at the line and column of the byte offset into the source
+fn syntax_error(source: &str, at: ErrorIndex, message: &str) -> Error {
+    let before = &source[..at];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    let mut error = Error::new(ErrorType::Syntax, message);
+    error.set_error_line(before.matches('\n').count() + 1);
+    error.set_error_index(before[line_start..].chars().count() + 1);
+
+    error
+}
+
+/// decodes the escapes of a string literal (quotes included), on failure we return the
+/// byte offset into the literal where the problem starts.
+fn decode_string(literal: &str) -> Result<String, (ErrorIndex, String)> {
+    let mut out = String::with_capacity(literal.len());
+    let mut chars = literal.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            // the tokenizer regex only lets an unescaped quote through as the final character
+            '"' => return Ok(out),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, 't')) => out.push('\t'),
+                Some((_, 'r')) => out.push('\r'),
+                Some((_, '\\')) => out.push('\\'),
+                Some((_, '"')) => out.push('"'),
+                Some((_, 'u')) => {
+                    if chars.next().map(|(_, c)| c) != Some('{') {
+                        return Err((i, "unicode escapes are written as \\u{...}".to_string()))
+                    }
+
+                    let mut digits = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, d)) if d.is_ascii_hexdigit() && digits.len() < 6 => digits.push(d),
+                            _ => return Err((i, "malformed unicode escape".to_string()))
+                        }
+                    }
+
+                    match u32::from_str_radix(&digits, 16).ok().and_then(std::char::from_u32) {
+                        Some(decoded) => out.push(decoded),
+                        None => return Err((i, format!("invalid unicode escape \\u{{{}}}", digits)))
+                    }
+                },
+                Some((_, other)) => return Err((i, format!("invalid escape \\{}", other))),
+                None => break
+            },
+            _ => out.push(c)
+        }
+    }
+
+    Err((0, "unclosed string".to_string()))
+}
+
+/// names of the characters that can't be written directly after the backslash
+pub const CHAR_NAMES: [(&str, char); 6] = [
+    ("newline", '\n'),
+    ("space", ' '),
+    ("tab", '\t'),
+    ("return", '\r'),
+    ("backspace", '\u{8}'),
+    ("formfeed", '\u{c}')
+];
+
+/// decodes a character literal (backslash included), either a single character like \a or \λ,
+/// a name like \newline, or a unicode escape like \u03bb.
+fn decode_char(literal: &str) -> Result<char, String> {
+    let body = &literal[1..];
+    let mut chars = body.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c)
+    }
+
+    if let Some((_, c)) = CHAR_NAMES.iter().find(|(name, _)| *name == body) {
+        return Ok(*c)
+    }
+
+    match body.strip_prefix('u') {
+        Some(hex) if hex.len() == 4 && hex.chars().all(|c| c.is_ascii_hexdigit()) => u32::from_str_radix(hex, 16).ok()
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| format!("invalid unicode character {}", literal)),
+        _ => Err(format!("unknown character {}", literal))
+    }
+}
 
 impl Tokenizer {
 
@@ -68,14 +163,18 @@ impl Tokenizer {
 
         let re = Regex::new(r#"(?x)
             [\s]* #skip white spaces
-            (?P<special_two>~@)
+            (?P<special_two>~@|\#\{)
             |
             (?P<special_one>[\[\]{}()'`~^@])
             |
+            (?P<regex>\#"(?:\\.|[^\\"])*"?)
+            |
             (?P<string>"(?:\\.|[^\\"])*"?)
             |
             (?P<comment>;.*)
             |
+            (?P<char>\\\S[^\s\[\]{}('"`,;)]*)
+            |
             (?P<symbol>[^\s\[\]{}('"`,;)]+)"#);
 
         Self{re: re.unwrap()}
@@ -85,7 +184,8 @@ impl Tokenizer {
 
         let mut v = VecDeque::new();
 
-        let mut parentheses_count = 0; // needs to end at zero, otherwise syntax error
+        // every opening bracket needs a matching closing one of the same kind, otherwise syntax error
+        let mut open_brackets = Vec::new();
 
         for cap in self.re.captures_iter(&line) {
             // need to fix this at some point...
@@ -94,30 +194,66 @@ impl Tokenizer {
             }
 
             if let Some(m) = cap.name("symbol") {
-                v.push_back(Token::new(m.as_str().to_string(), TokenType::Symbol));
+                let s = m.as_str();
+
+                // numbers are validated here, where we still know the position, the reader converts them
+                if is_numeric(s) {
+                    if let Err(message) = parse_number(s) {
+                        return Err(syntax_error(&line, m.start(), &message))
+                    }
+
+                    v.push_back(Token::new(s.to_string(), TokenType::Number));
+                } else {
+                    v.push_back(Token::new(s.to_string(), TokenType::Symbol));
+                }
 
             } else if let Some(m) = cap.name("string") {
-                let last_char = m.as_str().chars().last().unwrap();
+                // string tokens carry the decoded contents, without the quotes
+                match decode_string(m.as_str()) {
+                    Ok(s) => v.push_back(Token::new(s, TokenType::String)),
+                    Err((offset, message)) => {
+                        return Err(syntax_error(&line, m.start() + offset, &message))
+                    }
+                }
+
+            } else if let Some(m) = cap.name("char") {
+                // like strings, char tokens carry the decoded character
+                match decode_char(m.as_str()) {
+                    Ok(c) => v.push_back(Token::new(c.to_string(), TokenType::Char)),
+                    Err(message) => return Err(syntax_error(&line, m.start(), &message))
+                }
 
-                if last_char != '"' {
-                    return Err(Error::new(ErrorType::Syntax, "unclosed string"));
+            } else if let Some(m) = cap.name("regex") {
+                if m.as_str().len() < 3 || !m.as_str().ends_with('"') {
+                    return Err(syntax_error(&line, m.start(), "unclosed regex literal"));
                 }
 
-                v.push_back(Token::new(m.as_str().to_string(), TokenType::String));
+                v.push_back(Token::new(m.as_str().to_string(), TokenType::Regex));
 
             } else if let Some(m) = cap.name("special_one") {
                 let s = m.as_str();
 
-                if s == "(" {
-                    parentheses_count += 1;
-                } else if s == ")" {
-                    parentheses_count -= 1;
+                match s {
+                    "(" => open_brackets.push(")"),
+                    "{" => open_brackets.push("}"),
+                    "[" => open_brackets.push("]"),
+                    ")" | "}" | "]" => match open_brackets.pop() {
+                        Some(expected) if expected == s => (),
+                        Some(_) => return Err(syntax_error(&line, m.start(), "mismatched closing bracket")),
+                        None => return Err(Error::new(ErrorType::Syntax, "too few opening parentheses"))
+                    },
+                    _ => ()
                 }
 
                 v.push_back(Token::new(s.to_string(),
                                   TokenType::SpecialOne));
 
             } else if let Some(m) = cap.name("special_two") {
+                // #{ opens a set literal, which is closed like a map
+                if m.as_str() == "#{" {
+                    open_brackets.push("}");
+                }
+
                 v.push_back(Token::new(m.as_str().to_string(),
                                   TokenType::SpecialTwo));
 
@@ -127,20 +263,27 @@ impl Tokenizer {
             }
         }
 
-        match parentheses_count {
-            x if x < 0 => Err(Error::new(ErrorType::Syntax, "too few opening parentheses")),
-            x if x > 0 => Err(Error::new(ErrorType::Syntax, "too few closing parentheses")),
-            _ => Ok(v)
+        if open_brackets.is_empty() {
+            Ok(v)
+        } else {
+            Err(Error::new(ErrorType::Syntax, "too few closing parentheses"))
         }
 
     }
 
 }
 
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
+#[allow(unused_variables, clippy::assertions_on_constants)]
 mod test {
 
-    use super::Tokenizer;
+    use super::{Tokenizer, TokenType};
 
     #[test]
     fn tokenize() {
@@ -181,6 +324,9 @@ mod test {
             Err(m) => assert!(true),
             Ok(result) => assert!(false)
         }
+
+        assert!(r.tokenize("{:a (1}".to_string()).is_err());
+        assert!(r.tokenize("(+ 1))".to_string()).is_err());
     }
 
     #[test]
@@ -197,6 +343,51 @@ mod test {
         }
     }
 
+    #[test]
+    fn regex_literal() {
+        let r = Tokenizer::new();
+        let line = r#"(re-find #"a\"(\d+)" s)"#.to_string();
+
+        match r.tokenize(line) {
+            Err(_) => panic!("tokenizer rejected valid input"),
+            Ok(result) => {
+                assert_eq!(result[2].get_type(), TokenType::Regex);
+                assert_eq!(result[2].get_text(), r#"#"a\"(\d+)""#);
+                assert_eq!(result[3].get_text(), "s");
+            }
+        }
+
+        assert!(r.tokenize(r#"#"abc"#.to_string()).is_err());
+    }
+
+    #[test]
+    fn string_escapes() {
+        let r = Tokenizer::new();
+        let line = r#"(str "a\tb\r\\ \"q\" \u{3bb}")"#.to_string();
+
+        match r.tokenize(line) {
+            Err(_) => panic!("tokenizer rejected valid input"),
+            Ok(result) => {
+                assert_eq!(result[2].get_type(), TokenType::String);
+                assert_eq!(result[2].get_text(), "a\tb\r\\ \"q\" \u{3bb}");
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_escapes_report_position() {
+        let r = Tokenizer::new();
+
+        match r.tokenize("(str\n  \"ab\\q\")".to_string()) {
+            Ok(_) => panic!("invalid escape was accepted"),
+            Err(e) => assert_eq!(e.to_string(), "Syntax error - invalid escape \\q at line 2, column 6")
+        }
+
+        assert!(r.tokenize(r#""\u{110000}""#.to_string()).is_err());
+        assert!(r.tokenize(r#""\u3bb""#.to_string()).is_err());
+        assert!(r.tokenize(r#""abc\""#.to_string()).is_err());
+    }
+
     #[test]
     fn test_new_lines() {
         let r = Tokenizer::new();
@@ -1,50 +1,191 @@
 use super::reader::parser::Parser;
-use super::unit::Unit;
+use super::symbol::Symbol;
 use super::list::List;
+use super::map::Map;
+use super::set::Set;
+use super::lazy::{LazySeq, seq_equal};
+use super::record::Record;
+use super::function::Function;
+use super::pattern::Pattern;
+use super::printer::pr_str;
 use crate::types::env::Scope;
 use std::rc::Rc;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::cmp::Ordering;
+use num_bigint::BigInt;
+use num_rational::BigRational;
 use std::fmt::{Display, Formatter, Result};
 use std::cell::RefCell;
-use crate::types::reader_macros::{at_macro, quote_macro};
+use crate::types::reader_macros::{at_macro, quote_macro, meta_macro};
+use crate::reader::tokenizer::TokenType;
+use super::numeric::{numeric_ordering, exact_value, to_float, normalize_ratio};
+use crate::reader::number::{parse_number, Number};
 
 pub type Lambda = Rc<dyn Fn(&List, &mut Scope) -> LispValue>;
 
+/// a function made by lambda or defn, called with its args, the caller's scope and the scope it closes over
+pub type Closure = Rc<dyn Fn(&List, &mut Scope, &Scope) -> LispValue>;
+
 #[derive(Clone)]
 pub enum LispValue {
     List(List),
-    Unit(Unit),
+    // items are only computed when something walks the seq, see lazy.rs
+    LazySeq(LazySeq),
+    Map(Map),
+    Set(Set),
+    // made by the constructors defrecord defines, prints as #Name{:field value ...}
+    Record(Record),
+    Symbol(Symbol),
     Int(i64),
+    // ints get promoted to big ints when an operation would overflow
+    BigInt(BigInt),
+    // exact results of int division, always reduced and never with a denominator of one
+    Ratio(BigRational),
     Float(f64),
     Nil,
     Boolean(bool),
-    Function(Lambda),
+    Function(Function),
     Error(String),
     String(String),
+    // read from \a, \newline or \u03bb
+    Char(char),
+    // :name, stored without the colon. keywords evaluate to themselves
+    Keyword(String),
     // we need interior mutability here since we can potentially "swap" and "mutate" the contents of an atom
     // they are inspired by the clojure atoms.
     Atom(Rc<RefCell<LispValue>>),
-    Macro(Lambda)
+    Macro(Function),
+    // compiled once when read (or by re-pattern) and reused on every match
+    Regex(Pattern),
+    // returned by recur, the enclosing loop or function rebinds its arguments to these values and runs again
+    Recur(Vec<LispValue>)
 }
 
 pub type AST = LispValue;
 
-impl Display for LispValue {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+/// structural equality: lists compare item by item, numbers compare by their exact value across
+/// ints, ratios and floats, while atoms, functions and macros are only equal to themselves.
+/// unlike in arithmetic comparisons NaN is equal to itself, so equality is an equivalence and
+/// any value, NaN included, can be used as a key.
+impl PartialEq for LispValue {
+    fn eq(&self, other: &LispValue) -> bool {
+        match (self, other) {
+            (LispValue::Nil, LispValue::Nil) => true,
+            (LispValue::Boolean(a), LispValue::Boolean(b)) => a == b,
+            (LispValue::String(a), LispValue::String(b)) => a == b,
+            (LispValue::Char(a), LispValue::Char(b)) => a == b,
+            (LispValue::Keyword(a), LispValue::Keyword(b)) => a == b,
+            (LispValue::Error(a), LispValue::Error(b)) => a == b,
+            (LispValue::Symbol(a), LispValue::Symbol(b)) => a.name() == b.name(),
+            (LispValue::List(a), LispValue::List(b)) => a == b,
+            (LispValue::LazySeq(a), LispValue::LazySeq(b)) if a.ptr_eq(b) => true,
+            (LispValue::LazySeq(_), LispValue::List(_))
+            | (LispValue::List(_), LispValue::LazySeq(_))
+            | (LispValue::LazySeq(_), LispValue::LazySeq(_)) => seq_equal(self, other),
+            (LispValue::Map(a), LispValue::Map(b)) => a == b,
+            (LispValue::Set(a), LispValue::Set(b)) => a == b,
+            (LispValue::Record(a), LispValue::Record(b)) => a == b,
+            (LispValue::Regex(a), LispValue::Regex(b)) => a.source() == b.source(),
+            (LispValue::Atom(a), LispValue::Atom(b)) => Rc::ptr_eq(a, b),
+            (LispValue::Function(a), LispValue::Function(b)) => a.ptr_eq(b),
+            (LispValue::Macro(a), LispValue::Macro(b)) => a.ptr_eq(b),
+            (LispValue::Float(a), LispValue::Float(b)) if a.is_nan() && b.is_nan() => true,
+            (a, b) => numeric_ordering(a, b) == Some(Ordering::Equal)
+        }
+    }
+}
+
+impl Eq for LispValue {}
+
+/// hashing has to agree with equality: numbers equal across types hash alike, so they are hashed
+/// by their exact value, and maps and sets hash the same whatever order their entries are in.
+impl Hash for LispValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // a lazy seq is equal to the list of its items, so it hashes like one
+        if let LispValue::LazySeq(s) = self {
+            return match s.iter().take(HASH_PREFIX + 1).collect::<std::result::Result<Vec<LispValue>, String>>() {
+                Ok(items) => {
+                    std::mem::discriminant(&LispValue::List(List::new())).hash(state);
+                    hash_seq(items.iter().take(HASH_PREFIX), items.len(), state)
+                },
+                Err(e) => LispValue::Error(e).hash(state)
+            }
+        }
 
         match self {
-            LispValue::Int(i) => write!(f, "{}", i),
-            LispValue::Float(float) => write!(f, "{}", float),
-            LispValue::Error(message) => write!(f, "error - {}", message),
-            LispValue::Boolean(b) => write!(f, "{}", b),
-            LispValue::Nil => write!(f, "nil"),
-            LispValue::Function(_l) => write!(f, "#<lambda>"),
-            LispValue::String(s) => write!(f, "{}", s),
-            LispValue::List(l) => write!(f, "{}", l),
-            LispValue::Unit(a) => write!(f, "{}", a),
-            LispValue::Atom(b) => write!(f, "Atom <{}>", b.as_ref().borrow()),
-            LispValue::Macro(_l) => write!(f, "#<macro>")
+            LispValue::Int(_) | LispValue::BigInt(_) | LispValue::Ratio(_) | LispValue::Float(_) => hash_number(self, state),
+            _ => std::mem::discriminant(self).hash(state)
         }
 
+        match self {
+            LispValue::Int(_) | LispValue::BigInt(_) | LispValue::Ratio(_) | LispValue::Float(_) | LispValue::Nil
+            | LispValue::LazySeq(_) => (),
+            LispValue::Boolean(b) => b.hash(state),
+            LispValue::String(s) | LispValue::Keyword(s) | LispValue::Error(s) => s.hash(state),
+            LispValue::Char(c) => c.hash(state),
+            LispValue::Symbol(s) => s.name().hash(state),
+            LispValue::List(l) => hash_seq(l.iter().take(HASH_PREFIX), l.len(), state),
+            LispValue::Recur(values) => values.hash(state),
+            LispValue::Map(m) => unordered_hash(m.iter().map(hash_value)).hash(state),
+            LispValue::Set(s) => unordered_hash(s.iter().map(hash_value)).hash(state),
+            LispValue::Record(r) => {
+                r.name().hash(state);
+                unordered_hash(r.fields().iter().map(hash_value)).hash(state)
+            },
+            LispValue::Regex(p) => p.source().hash(state),
+            LispValue::Atom(a) => Rc::as_ptr(a).hash(state),
+            LispValue::Function(f) | LispValue::Macro(f) => f.address().hash(state)
+        }
+    }
+}
+
+/// lists and lazy seqs only hash their first HASH_PREFIX items, so an infinite seq can be hashed
+/// (and used as a key) without realizing it all. longer seqs that share those items hash alike.
+const HASH_PREFIX: usize = 256;
+
+/// hashes the length of a seq and its first items, a seq longer than HASH_PREFIX hashes a marker
+/// instead of its length since a lazy seq's isn't known
+fn hash_seq<'a, H: Hasher>(items: impl Iterator<Item = &'a LispValue>, len: usize, state: &mut H) {
+    if len > HASH_PREFIX { usize::MAX } else { len }.hash(state);
+    items.for_each(|item| item.hash(state));
+}
+
+/// numbers hash through their normalized exact value, so 2, 2.0 and 4/2 all hash as the int 2.
+/// infinities and NaN have no exact value and hash their float bits, NaN in a single canonical form.
+fn hash_number<H: Hasher>(value: &LispValue, state: &mut H) {
+    if let LispValue::Int(i) = value {
+        return i.hash(state)
+    }
+
+    match exact_value(value).map(normalize_ratio) {
+        Some(LispValue::Int(i)) => i.hash(state),
+        Some(LispValue::BigInt(b)) => b.hash(state),
+        Some(LispValue::Ratio(r)) => r.hash(state),
+        _ => match to_float(value) {
+            Some(f) if f.is_nan() => f64::NAN.to_bits().hash(state),
+            Some(f) => f.to_bits().hash(state),
+            None => ()
+        }
+    }
+}
+
+fn unordered_hash(hashes: impl Iterator<Item = u64>) -> u64 {
+    hashes.fold(0, u64::wrapping_add)
+}
+
+/// the structural hash of a value, the same for equal values within a run
+pub fn hash_value<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+
+    hasher.finish()
+}
+
+// display prints values the way str and println do, use printer::pr_str for the readable form
+impl Display for LispValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", pr_str(self, false))
     }
 }
 
@@ -55,18 +196,34 @@ pub fn build_ast(parser: &mut Parser) -> LispValue {
 
 
 pub (crate) fn read_form(parser: &mut Parser) -> LispValue {
+    match parser.peek().unwrap().get_type() {
+        TokenType::Regex => return read_regex(parser),
+        // the tokenizer has already decoded the escapes
+        TokenType::String => return LispValue::String(parser.next().unwrap().get_text().clone()),
+        TokenType::Number => return read_number(parser),
+        // the tokenizer has already decoded the character
+        TokenType::Char => return read_char(parser),
+        _ => ()
+    }
+
     match parser.peek().unwrap().get_text().as_str() {
         "(" => LispValue::List(read_list(parser)),
+        "{" => read_map(parser),
+        "#{" => read_set(parser),
         "@" => at_macro(parser),
         "'" => quote_macro(parser),
-        _ => LispValue::Unit(read_atom(parser)),
+        "^" => meta_macro(parser),
+        text if text.len() > 1 && text.starts_with(':') => {
+            LispValue::Keyword(parser.next().unwrap().get_text()[1..].to_string())
+        },
+        _ => LispValue::Symbol(read_atom(parser)),
     }
 }
 
 fn read_list(parser: &mut Parser) -> List {
     parser.next();
 
-    let mut l = List::new();
+    let mut items = Vec::new();
     // we've confirmed that there's always a matching ")"
     loop {
         match parser.peek().unwrap().get_text().as_str() {
@@ -74,13 +231,101 @@ fn read_list(parser: &mut Parser) -> List {
                 parser.next();
                 break;
             },
-            _ => l.push(read_form(parser))
+            _ => items.push(read_form(parser))
+        };
+    }
+
+    List::from_vec(items)
+}
+
+fn read_char(parser: &mut Parser) -> LispValue {
+    match parser.next().unwrap().get_text().chars().next() {
+        Some(c) => LispValue::Char(c),
+        None => LispValue::Error("empty character literal".to_string())
+    }
+}
+
+// numeric literals are parsed once here instead of on every evaluation
+fn read_number(parser: &mut Parser) -> LispValue {
+    match parse_number(parser.next().unwrap().get_text()) {
+        Ok(Number::Int(i)) => LispValue::Int(i),
+        Ok(Number::BigInt(b)) => LispValue::BigInt(b),
+        Ok(Number::Ratio(r)) => LispValue::Ratio(r),
+        Ok(Number::Float(f)) => LispValue::Float(f),
+        Err(e) => LispValue::Error(e)
+    }
+}
+
+// regex literals are compiled at read time, the pattern text is handed to the regex crate as is
+fn read_regex(parser: &mut Parser) -> LispValue {
+    let token = parser.next().unwrap();
+    let text = token.get_text();
+
+    // strip the leading #" and the closing "
+    match Pattern::new(&text[2..text.len() - 1]) {
+        Ok(p) => LispValue::Regex(p),
+        Err(e) => LispValue::Error(e.to_string())
+    }
+}
+
+// the keys and values of a map literal are evaluated like the items of a list
+fn read_map(parser: &mut Parser) -> LispValue {
+    parser.next();
+
+    let mut forms = Vec::new();
+
+    loop {
+        match parser.peek().unwrap().get_text().as_str() {
+            "}" => {
+                parser.next();
+                break;
+            },
+            _ => forms.push(read_form(parser))
+        };
+    }
+
+    if forms.len() % 2 != 0 {
+        return LispValue::Error("map literal needs an even number of forms".to_string())
+    }
+
+    let mut map = Map::new();
+    let mut iter = forms.into_iter();
+
+    while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
+        if map.contains_key(&key) {
+            return LispValue::Error(format!("duplicate key in map literal: {}", pr_str(&key, true)))
+        }
+
+        map.insert(key, value);
+    }
+
+    LispValue::Map(map)
+}
+
+fn read_set(parser: &mut Parser) -> LispValue {
+    parser.next();
+
+    let mut set = Set::new();
+
+    loop {
+        match parser.peek().unwrap().get_text().as_str() {
+            "}" => {
+                parser.next();
+                break;
+            },
+            _ => {
+                let form = read_form(parser);
+
+                if !set.insert(form.clone()) {
+                    return LispValue::Error(format!("duplicate element in set literal: {}", pr_str(&form, true)))
+                }
+            }
         };
     }
 
-    l
+    LispValue::Set(set)
 }
 
-fn read_atom(parser: &mut Parser) -> Unit {
-    Unit::new(parser.next().unwrap())
+fn read_atom(parser: &mut Parser) -> Symbol {
+    Symbol::new(parser.next().unwrap().get_text())
 }
@@ -1,37 +1,109 @@
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 
 use crate::exec::core_atom::{apply_atom, apply_deref, apply_is_atom, apply_reset, apply_swap};
-use crate::exec::core_list::{apply_concat, apply_cons};
-use crate::exec::core_recursive::{apply_do, apply_let, apply_if, create_func, apply_def};
+use crate::exec::core_list::{apply_concat, apply_cons, apply_seq, apply_first, apply_rest, apply_count, apply_is_empty};
+use crate::exec::core_char::{apply_char, apply_int, apply_is_char};
+use crate::exec::core_recursive::{apply_do, apply_let, apply_if, create_func, apply_def, apply_defn, apply_try,
+                                  apply_and, apply_or, apply_not, apply_when, apply_when_not, apply_cond,
+                                  apply_case, apply_loop, apply_recur};
 use crate::exec::core_comparison::{apply_equals, apply_greater_than, apply_greater_than_equals,
-                                   apply_less_than, apply_less_than_equals};
-
-use crate::exec::core_file::{apply_slurp};
-use crate::exec::core_meta::{apply_quote, apply_macro};
-use crate::exec::math::{add, sub, mul, div};
-use crate::exec::core_utils::{apply_list, apply_eval, apply_str, apply_read_string, apply_prn};
+                                   apply_less_than, apply_less_than_equals, apply_not_equals,
+                                   apply_compare, apply_identical, apply_hash};
+
+use crate::exec::core_file::{apply_slurp, apply_line_seq};
+use crate::exec::core_ns::{apply_ns, apply_require, apply_refer, apply_alias, LOAD_PATH};
+use crate::exec::core_record::{apply_defrecord, apply_is_record};
+use crate::exec::core_multi::{apply_defmulti, apply_defmethod};
+use crate::exec::core_type::{apply_type, apply_arity, apply_is_int, apply_is_bigint, apply_is_ratio,
+                             apply_is_float, apply_is_number, apply_is_nil, apply_is_some,
+                             apply_is_boolean, apply_is_true, apply_is_false, apply_is_string,
+                             apply_is_keyword, apply_is_regex, apply_is_list, apply_is_lazy_seq,
+                             apply_is_seq, apply_is_coll, apply_is_fn, apply_is_macro,
+                             apply_is_callable};
+use crate::exec::core_lazy::{apply_lazy_seq, apply_range, apply_iterate, apply_repeat, apply_cycle, apply_map,
+                              apply_filter, apply_take, apply_doall, apply_is_realized};
+use crate::exec::core_map::{apply_hash_map, apply_get, apply_assoc, apply_dissoc, apply_keys, apply_vals,
+                             apply_is_map};
+use crate::exec::core_set::{apply_hash_set, apply_is_set, apply_conj, apply_disj, apply_contains, apply_union,
+                             apply_intersection, apply_difference};
+use crate::exec::core_regex::{apply_re_pattern, apply_re_find, apply_re_matches, apply_re_seq,
+                              apply_re_replace};
+use crate::exec::core_meta::{apply_quote, apply_macro, apply_with_meta, apply_meta};
+use crate::exec::core_symbol::{apply_symbol, apply_is_symbol, apply_name, apply_gensym};
+use crate::exec::core_doc::{apply_doc, apply_apropos, apply_dir};
+use crate::exec::math::{add, sub, mul, div, AUTO_PROMOTE, apply_numerator, apply_denominator,
+                        apply_is_rational, apply_quot, apply_rem, apply_mod, apply_abs, apply_min,
+                        apply_max, apply_pow, apply_sqrt, apply_exp, apply_log, apply_sin, apply_cos,
+                        apply_tan, apply_floor, apply_ceil, apply_round, apply_inc, apply_dec,
+                        apply_is_zero, apply_is_pos, apply_is_neg, apply_is_even, apply_is_odd};
+use crate::exec::core_utils::{apply_list, apply_eval, apply_str, apply_read_string, apply_prn,
+                              apply_pr_str, apply_println};
 
 
 
 use crate::types::ast::LispValue;
+use crate::types::list::List;
+use crate::types::function::Function;
 
 
+/// cloning a scope shares its bindings, it doesn't copy them
+#[derive(Clone)]
 pub struct Scope {
     current: Rc<RefCell<Env>>
 }
 
+/// a scope that doesn't keep its bindings alive
+#[derive(Clone)]
+pub struct WeakScope {
+    current: Weak<RefCell<Env>>
+}
+
+impl WeakScope {
+    pub fn upgrade(&self) -> Option<Scope> {
+        self.current.upgrade().map(|current| Scope { current })
+    }
+}
+
+/// what the help system knows about a binding, a usage line like (inc x) and a docstring
+#[derive(Clone, Default)]
+pub struct Doc {
+    pub usage: Option<String>,
+    pub text: Option<String>
+}
+
 pub struct Env {
     data: HashMap<String, LispValue>,
-    outer: Option<Rc<RefCell<Env>>>
+    docs: HashMap<String, Doc>,
+    outer: Option<Rc<RefCell<Env>>>,
+    // only filled in at the root, which holds the builtins, every namespace including user
+    namespaces: HashMap<String, Scope>
+}
+
+/// the hidden binding naming the namespace a scope belongs to. every namespace, user too, is a
+/// child of the root, so they share the builtins but not each other's definitions.
+const NAMESPACE: &str = " ns";
+
+fn alias_key(alias: &str) -> String {
+    format!(" alias {}", alias)
 }
 
 impl Env {
-    pub fn get(&self, key: &String) -> Option<LispValue> {
-        // safe to unwrap here since find will insure that the key exists.
-        self.data.get(key).map( | ref_result| {
-            ref_result.clone()
+    fn new(outer: Option<Rc<RefCell<Env>>>) -> Self {
+        Env {
+            data: HashMap::new(),
+            docs: HashMap::new(),
+            outer,
+            namespaces: HashMap::new()
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<LispValue> {
+        self.data.get(key).map(|value| match value {
+            LispValue::Function(f) => LispValue::Function(f.looked_up()),
+            LispValue::Macro(f) => LispValue::Macro(f.looked_up()),
+            other => other.clone()
         })
     }
 
@@ -42,13 +114,17 @@ impl Env {
 
 macro_rules! to_func {
     ($f:expr) => {
-        LispValue::Function(Rc::new($f))
+        LispValue::Function(Function::new(Rc::new($f)))
     }
 }
 
 macro_rules! insert {
-    ($m:expr, $key:literal, $func:ident) => {
+    ($m:expr, $key:literal, $func:ident, $usage:literal, $doc:literal) => {
          $m.insert($key.to_string(), to_func!($func));
+         $m.docs.insert($key.to_string(), Doc {
+             usage: Some($usage.to_string()),
+             text: Some($doc.to_string())
+         });
     }
 }
 
@@ -56,76 +132,542 @@ impl Scope {
 
     // the math functions live at the base scope level, which also means
     // that they can actually be pretty freely redefined.
+    // what comes back is the user namespace, a child of that base scope.
     pub fn new() -> Self {
-        let mut map: HashMap<String, LispValue> = HashMap::new();
-        insert!(map, "+", add);
-        insert!(map, "-", sub);
-        insert!(map, "/", div);
-        insert!(map, "*", mul);
-
-        insert!(map, "do", apply_do);
-        insert!(map, "let", apply_let);
-        insert!(map, "if", apply_if);
-        insert!(map, "lambda", create_func);
-        insert!(map, "def!", apply_def);
-
-        insert!(map, "=", apply_equals);
-        insert!(map, ">", apply_greater_than);
-        insert!(map, "<", apply_less_than);
-        insert!(map, "<=", apply_less_than_equals);
-        insert!(map, ">=", apply_greater_than_equals);
-
-        insert!(map, "slurp", apply_slurp);
-        insert!(map, "list", apply_list);
-        insert!(map, "eval", apply_eval);
-        insert!(map, "str", apply_str);
-        insert!(map, "read-string", apply_read_string);
-        insert!(map, "prn", apply_prn);
-
-        insert!(map, "atom", apply_atom);
-        insert!(map, "atom?", apply_is_atom);
-        insert!(map, "deref", apply_deref);
-        insert!(map, "reset!", apply_reset);
-        insert!(map, "swap!", apply_swap);
-
-        insert!(map, "quote", apply_quote);
-        insert!(map, "cons", apply_cons);
-        insert!(map, "concat", apply_concat);
-        insert!(map, "macro!", apply_macro);
-
-        let env = Rc::new(RefCell::new(Env {
-            data: map,
-            outer: None
-        }));
-
-        Scope{
-            current: env
-        }
+        let mut env = Env::new(None);
+
+        insert!(env, "+", add,
+                "(+ & nums)",
+                "returns the sum of nums, (+) is 0. ints that overflow are promoted to big ints while *auto-promote* is true");
+        insert!(env, "-", sub,
+                "(- x & nums)",
+                "subtracts nums from x, with a single argument negates x");
+        insert!(env, "/", div,
+                "(/ x & nums)",
+                "divides x by nums, with a single argument gives 1/x. exact division of ints gives a ratio, dividing by zero is an error");
+        insert!(env, "*", mul,
+                "(* & nums)",
+                "returns the product of nums, (*) is 1");
+        env.insert(LOAD_PATH.to_string(), LispValue::List(List::from_vec(vec![LispValue::String(".".to_string())])));
+        env.docs.insert(LOAD_PATH.to_string(), Doc {
+            usage: None,
+            text: Some("the directories require looks in for the file of a namespace, in order".to_string())
+        });
+
+        env.insert(AUTO_PROMOTE.to_string(), LispValue::Boolean(true));
+        env.docs.insert(AUTO_PROMOTE.to_string(), Doc {
+            usage: None,
+            text: Some("when true ints that overflow are promoted to big ints, otherwise overflow is an error".to_string())
+        });
+        insert!(env, "numerator", apply_numerator,
+                "(numerator r)",
+                "the numerator of a ratio, ints are their own numerator");
+        insert!(env, "denominator", apply_denominator,
+                "(denominator r)",
+                "the denominator of a ratio, 1 for ints");
+        insert!(env, "rational?", apply_is_rational,
+                "(rational? x)",
+                "true when x is an int, big int or ratio");
+        insert!(env, "quot", apply_quot,
+                "(quot n d)",
+                "integer division truncated towards zero");
+        insert!(env, "rem", apply_rem,
+                "(rem n d)",
+                "remainder of quot, takes the sign of n");
+        insert!(env, "mod", apply_mod,
+                "(mod n d)",
+                "modulus of n and d, takes the sign of d");
+        insert!(env, "abs", apply_abs,
+                "(abs x)",
+                "absolute value of x");
+        insert!(env, "min", apply_min,
+                "(min x & more)",
+                "smallest of the numbers");
+        insert!(env, "max", apply_max,
+                "(max x & more)",
+                "largest of the numbers");
+        insert!(env, "pow", apply_pow,
+                "(pow base exponent)",
+                "base raised to exponent, exact for ints with a non negative exponent");
+        insert!(env, "sqrt", apply_sqrt,
+                "(sqrt x)",
+                "square root of x as a float");
+        insert!(env, "exp", apply_exp,
+                "(exp x)",
+                "e raised to x");
+        insert!(env, "log", apply_log,
+                "(log x)",
+                "natural logarithm of x");
+        insert!(env, "sin", apply_sin,
+                "(sin x)",
+                "sine of x in radians");
+        insert!(env, "cos", apply_cos,
+                "(cos x)",
+                "cosine of x in radians");
+        insert!(env, "tan", apply_tan,
+                "(tan x)",
+                "tangent of x in radians");
+        insert!(env, "floor", apply_floor,
+                "(floor x)",
+                "the largest integer not greater than x, as an int");
+        insert!(env, "ceil", apply_ceil,
+                "(ceil x)",
+                "the smallest integer not less than x, as an int");
+        insert!(env, "round", apply_round,
+                "(round x)",
+                "x rounded to the nearest int, halves round away from zero so (round -2.5) is -3");
+        insert!(env, "inc", apply_inc,
+                "(inc x)",
+                "x plus one");
+        insert!(env, "dec", apply_dec,
+                "(dec x)",
+                "x minus one");
+        insert!(env, "zero?", apply_is_zero,
+                "(zero? x)",
+                "true when the number x is zero");
+        insert!(env, "pos?", apply_is_pos,
+                "(pos? x)",
+                "true when the number x is greater than zero");
+        insert!(env, "neg?", apply_is_neg,
+                "(neg? x)",
+                "true when the number x is less than zero");
+        insert!(env, "even?", apply_is_even,
+                "(even? n)",
+                "true when the integer n is even");
+        insert!(env, "odd?", apply_is_odd,
+                "(odd? n)",
+                "true when the integer n is odd");
+
+        insert!(env, "do", apply_do,
+                "(do & forms)",
+                "evaluates the forms in order and returns the last value, nil when empty");
+        insert!(env, "let", apply_let,
+                "(let (pattern value ...) & body)",
+                "binds each pattern to its value, destructuring lists and maps, then evaluates the body");
+        insert!(env, "if", apply_if,
+                "(if test then else?)",
+                "evaluates then when test is truthy, else otherwise. only nil and false are falsy");
+        insert!(env, "lambda", create_func,
+                "(lambda (params) & body) (lambda ((params) & body) ...)",
+                "creates a function, with one clause per arity when given several");
+        insert!(env, "def!", apply_def,
+                "(def! name docstring? value)",
+                "binds name to value in the current scope, the docstring shows up in doc");
+        insert!(env, "defn", apply_defn,
+                "(defn name docstring? (params) & body)",
+                "defines a named function, taking the same arities as lambda");
+        insert!(env, "try", apply_try,
+                "(try expr (catch e handler))",
+                "evaluates expr, when it is an error the message is bound to e while evaluating handler");
+        insert!(env, "and", apply_and,
+                "(and & forms)",
+                "returns the first falsy value or the last value, (and) is true");
+        insert!(env, "or", apply_or,
+                "(or & forms)",
+                "returns the first truthy value or the last value, (or) is nil");
+        insert!(env, "not", apply_not,
+                "(not x)",
+                "true when x is falsy, false otherwise");
+        insert!(env, "when", apply_when,
+                "(when test & body)",
+                "evaluates the body when test is truthy, nil otherwise");
+        insert!(env, "when-not", apply_when_not,
+                "(when-not test & body)",
+                "evaluates the body when test is falsy, nil otherwise");
+        insert!(env, "unless", apply_when_not,
+                "(unless test & body)",
+                "same as when-not");
+        insert!(env, "cond", apply_cond,
+                "(cond test expr ...)",
+                "evaluates the expression of the first truthy test, nil when nothing matches");
+        insert!(env, "case", apply_case,
+                "(case expr constant result ... default?)",
+                "returns the result of the constant equal to expr, a list of constants matches any of them");
+        insert!(env, "loop", apply_loop,
+                "(loop (pattern value ...) & body)",
+                "binds like let and runs the body, recur in tail position starts the next iteration");
+        insert!(env, "recur", apply_recur,
+                "(recur & values)",
+                "rebinds the enclosing loop or function to values and runs it again without growing the stack");
+
+        insert!(env, "=", apply_equals,
+                "(= x & more)",
+                "true when all values are structurally equal, numbers compare by value");
+        insert!(env, "not=", apply_not_equals,
+                "(not= x & more)",
+                "same as (not (= x & more))");
+        insert!(env, "compare", apply_compare,
+                "(compare x y)",
+                "-1, 0 or 1 depending on whether x is less than, equal to or greater than y. nil sorts before everything");
+        insert!(env, "identical?", apply_identical,
+                "(identical? x y)",
+                "true when x and y are the same object");
+        insert!(env, "hash", apply_hash,
+                "(hash x)",
+                "the structural hash of x, equal values have equal hashes. seqs only hash their first 256 items");
+        insert!(env, ">", apply_greater_than,
+                "(> x & more)",
+                "true when the values are in strictly decreasing order");
+        insert!(env, "<", apply_less_than,
+                "(< x & more)",
+                "true when the values are in strictly increasing order");
+        insert!(env, "<=", apply_less_than_equals,
+                "(<= x & more)",
+                "true when the values are in non decreasing order");
+        insert!(env, ">=", apply_greater_than_equals,
+                "(>= x & more)",
+                "true when the values are in non increasing order");
+
+        insert!(env, "slurp", apply_slurp,
+                "(slurp filename)",
+                "reads the whole file into a string");
+        insert!(env, "line-seq", apply_line_seq,
+                "(line-seq filename)",
+                "a lazy seq of the lines of the file, read as the seq is walked");
+        insert!(env, "list", apply_list,
+                "(list & items)",
+                "creates a list of the values of the items, which are evaluated like any args");
+        insert!(env, "eval", apply_eval,
+                "(eval form)",
+                "evaluates form at the top level of the current namespace");
+        insert!(env, "str", apply_str,
+                "(str & values)",
+                "concatenates the printed values, strings without quotes. lazy seqs print at most 1000 items");
+        insert!(env, "read-string", apply_read_string,
+                "(read-string s)",
+                "reads a single form from the string s without evaluating it");
+        insert!(env, "pr-str", apply_pr_str,
+                "(pr-str & values)",
+                "prints the values readably into a string, separated by spaces");
+        insert!(env, "prn", apply_prn,
+                "(prn & values)",
+                "prints the values readably followed by a newline, returns nil");
+        insert!(env, "println", apply_println,
+                "(println & values)",
+                "prints the values followed by a newline, returns nil");
+
+        insert!(env, "atom", apply_atom,
+                "(atom value)",
+                "creates an atom holding value");
+        insert!(env, "atom?", apply_is_atom,
+                "(atom? x)",
+                "true when x is an atom");
+        insert!(env, "deref", apply_deref,
+                "(deref atom)",
+                "the current value of the atom, also written @atom");
+        insert!(env, "reset!", apply_reset,
+                "(reset! atom value)",
+                "sets the atom to value and returns it");
+        insert!(env, "swap!", apply_swap,
+                "(swap! atom f)",
+                "sets the atom to the result of calling f on its current value");
+
+        insert!(env, "quote", apply_quote,
+                "(quote form)",
+                "returns form without evaluating it, also written 'form");
+        insert!(env, "cons", apply_cons,
+                "(cons x seq)",
+                "a new list or lazy seq with x in front of the items of seq, sharing them");
+        insert!(env, "concat", apply_concat,
+                "(concat & lists)",
+                "a new list with the items of all lists");
+        insert!(env, "first", apply_first,
+                "(first coll)",
+                "the first item of coll, nil when it is empty");
+        insert!(env, "rest", apply_rest,
+                "(rest coll)",
+                "the items of coll after the first, sharing them when coll is a list or seq");
+        insert!(env, "count", apply_count,
+                "(count coll)",
+                "the number of items in a list, seq, map, set or string, 0 for nil");
+        insert!(env, "empty?", apply_is_empty,
+                "(empty? coll)",
+                "true when coll has no items");
+        insert!(env, "seq", apply_seq,
+                "(seq coll)",
+                "a seq of the items of coll, the characters of a string or the (key value) entries of a map. nil when empty");
+
+        insert!(env, "defmacro!", apply_macro,
+                "(defmacro! name docstring? f)",
+                "same as macro!");
+        insert!(env, "macro!", apply_macro,
+                "(macro! name docstring? f)",
+                "turns the function f into a macro bound to name");
+
+        insert!(env, "lazy-seq", apply_lazy_seq,
+                "(lazy-seq body...)",
+                "a seq whose body is only evaluated, once, when the seq is first walked");
+        insert!(env, "range", apply_range,
+                "(range start? end? step?)",
+                "a lazy seq of numbers from start (0) up to but not including end, by step (1). infinite without an end");
+        insert!(env, "iterate", apply_iterate,
+                "(iterate f x)",
+                "the infinite lazy seq x, (f x), (f (f x)) ...");
+        insert!(env, "repeat", apply_repeat,
+                "(repeat n? x)",
+                "a lazy seq of n xs, or infinitely many without n");
+        insert!(env, "cycle", apply_cycle,
+                "(cycle coll)",
+                "an infinite lazy seq repeating the items of coll");
+        insert!(env, "map", apply_map,
+                "(map f coll & colls)",
+                "a lazy seq of f applied to the first items of the colls, then the second ones and so on");
+        insert!(env, "filter", apply_filter,
+                "(filter pred coll)",
+                "a lazy seq of the items of coll for which pred is truthy");
+        insert!(env, "take", apply_take,
+                "(take n coll)",
+                "a lazy seq of the first n items of coll");
+        insert!(env, "doall", apply_doall,
+                "(doall seq)",
+                "realizes the whole of a lazy seq and returns it");
+        insert!(env, "realized?", apply_is_realized,
+                "(realized? seq)",
+                "true when the first step of a lazy seq has been computed");
+
+        insert!(env, "char", apply_char,
+                "(char n)",
+                "the character with the code point n");
+        insert!(env, "int", apply_int,
+                "(int x)",
+                "the code point of the character x, numbers are truncated towards zero");
+        insert!(env, "char?", apply_is_char,
+                "(char? x)",
+                "true when x is a character");
+
+        insert!(env, "symbol", apply_symbol,
+                "(symbol name)",
+                "a symbol with the name of the string, keyword or symbol name");
+        insert!(env, "symbol?", apply_is_symbol,
+                "(symbol? x)",
+                "true when x is a symbol");
+        insert!(env, "name", apply_name,
+                "(name x)",
+                "the name of a symbol or keyword as a string");
+        insert!(env, "gensym", apply_gensym,
+                "(gensym prefix?)",
+                "a fresh symbol that is never returned again, for macros to bind temporaries to");
+
+        insert!(env, "with-meta", apply_with_meta,
+                "(with-meta value meta)",
+                "a copy of the function or collection value with the map meta attached, also written ^meta value");
+        insert!(env, "meta", apply_meta,
+                "(meta value)",
+                "the metadata map of value, nil when it has none");
+
+        insert!(env, "re-pattern", apply_re_pattern,
+                "(re-pattern s)",
+                "compiles the string s into a regex, same as the #\"...\" literal");
+        insert!(env, "re-find", apply_re_find,
+                "(re-find re s)",
+                "the first match of re in s, a list of the match and its groups when re has groups");
+        insert!(env, "re-matches", apply_re_matches,
+                "(re-matches re s)",
+                "like re-find, but re has to match the whole string");
+        insert!(env, "re-seq", apply_re_seq,
+                "(re-seq re s)",
+                "a list of every match of re in s");
+        insert!(env, "re-replace", apply_re_replace,
+                "(re-replace re s replacement)",
+                "replaces every match of re in s, $1 refers to the first group");
+
+        insert!(env, "hash-map", apply_hash_map,
+                "(hash-map & keyvals)",
+                "creates a map from alternating keys and values");
+        insert!(env, "get", apply_get,
+                "(get map key default?)",
+                "the value of key in map, default or nil when missing");
+        insert!(env, "assoc", apply_assoc,
+                "(assoc map & keyvals)",
+                "a new map with the keys set to the values");
+        insert!(env, "dissoc", apply_dissoc,
+                "(dissoc map & keys)",
+                "a new map without the keys");
+        insert!(env, "keys", apply_keys,
+                "(keys map)",
+                "a list of the keys of map");
+        insert!(env, "vals", apply_vals,
+                "(vals map)",
+                "a list of the values of map");
+        insert!(env, "map?", apply_is_map,
+                "(map? x)",
+                "true when x is a map");
+
+        insert!(env, "defrecord", apply_defrecord,
+                "(defrecord Name docstring? (field ...))",
+                "defines a record type with the constructors ->Name and map->Name, the predicate Name? and an accessor Name-field per field");
+        insert!(env, "record?", apply_is_record,
+                "(record? x)",
+                "true when x is a record of any type");
+
+        insert!(env, "type", apply_type,
+                "(type x)",
+                "a keyword for the kind of value x is, like :int, :string, :list or :fn. records give the name of their type, like :Point");
+        insert!(env, "int?", apply_is_int,
+                "(int? x)",
+                "true when x is an int or big int");
+        insert!(env, "bigint?", apply_is_bigint,
+                "(bigint? x)",
+                "true when x is a big int");
+        insert!(env, "ratio?", apply_is_ratio,
+                "(ratio? x)",
+                "true when x is a ratio");
+        insert!(env, "float?", apply_is_float,
+                "(float? x)",
+                "true when x is a float");
+        insert!(env, "number?", apply_is_number,
+                "(number? x)",
+                "true when x is any kind of number");
+        insert!(env, "nil?", apply_is_nil,
+                "(nil? x)",
+                "true when x is nil");
+        insert!(env, "some?", apply_is_some,
+                "(some? x)",
+                "true when x is anything but nil");
+        insert!(env, "boolean?", apply_is_boolean,
+                "(boolean? x)",
+                "true when x is true or false");
+        insert!(env, "true?", apply_is_true,
+                "(true? x)",
+                "true when x is the value true, not just truthy");
+        insert!(env, "false?", apply_is_false,
+                "(false? x)",
+                "true when x is the value false, nil is not false?");
+        insert!(env, "string?", apply_is_string,
+                "(string? x)",
+                "true when x is a string");
+        insert!(env, "keyword?", apply_is_keyword,
+                "(keyword? x)",
+                "true when x is a keyword");
+        insert!(env, "regex?", apply_is_regex,
+                "(regex? x)",
+                "true when x is a regex, from re-pattern or a #\"...\" literal");
+        insert!(env, "list?", apply_is_list,
+                "(list? x)",
+                "true when x is a list");
+        insert!(env, "lazy-seq?", apply_is_lazy_seq,
+                "(lazy-seq? x)",
+                "true when x is a lazy seq");
+        insert!(env, "seq?", apply_is_seq,
+                "(seq? x)",
+                "true when x is a list or a lazy seq");
+        insert!(env, "coll?", apply_is_coll,
+                "(coll? x)",
+                "true when x is a list, lazy seq, map, set or record");
+        insert!(env, "fn?", apply_is_fn,
+                "(fn? x)",
+                "true when x is a function, builtin or not");
+        insert!(env, "macro?", apply_is_macro,
+                "(macro? x)",
+                "true when x is a macro");
+        insert!(env, "callable?", apply_is_callable,
+                "(callable? x)",
+                "true when x can be called, that is a function or a macro");
+        insert!(env, "arity", apply_arity,
+                "(arity f)",
+                "how many args each arity of f takes, like (1 2 &) for ((a) (a b & more)). nil for builtins");
+        insert!(env, "ns", apply_ns,
+                "(ns name (:require spec...))",
+                "makes name the current namespace, creating it when new. later definitions land in it and other namespaces reach them as name/x");
+        insert!(env, "require", apply_require,
+                "(require spec... :reload?)",
+                "loads each namespace from its file on *load-path* unless it was loaded already. a spec is a name or (name :as alias :refer (names)), :refer :all takes every name");
+        insert!(env, "refer", apply_refer,
+                "(refer name :only (names)? :exclude (names)?)",
+                "binds the definitions of the namespace name in the current namespace so they can be used without the name/ prefix");
+        insert!(env, "alias", apply_alias,
+                "(alias a name)",
+                "lets a/x stand for name/x in the current namespace");
+        insert!(env, "defmulti", apply_defmulti,
+                "(defmulti name docstring? dispatch-fn)",
+                "defines a multimethod that calls the method registered for what dispatch-fn returns for the args");
+        insert!(env, "defmethod", apply_defmethod,
+                "(defmethod name dispatch-value (args list) body...)",
+                "adds the method of the multimethod name for dispatch-value, :default catches everything else");
+
+        insert!(env, "hash-set", apply_hash_set,
+                "(hash-set & items)",
+                "creates a set of the items, dropping duplicates");
+        insert!(env, "set?", apply_is_set,
+                "(set? x)",
+                "true when x is a set");
+        insert!(env, "conj", apply_conj,
+                "(conj coll & items)",
+                "adds the items to a set, to the front of a list, or as (key value) entries to a map");
+        insert!(env, "disj", apply_disj,
+                "(disj set & items)",
+                "a new set without the items");
+        insert!(env, "contains?", apply_contains,
+                "(contains? coll key)",
+                "true when the set coll has the element key, or the map coll has the key");
+        insert!(env, "union", apply_union,
+                "(union & sets)",
+                "a set of the elements in any of the sets");
+        insert!(env, "intersection", apply_intersection,
+                "(intersection set & sets)",
+                "a set of the elements of set that are in every other set");
+        insert!(env, "difference", apply_difference,
+                "(difference set & sets)",
+                "a set of the elements of set that are in none of the other sets");
+
+        insert!(env, "doc", apply_doc,
+                "(doc name)",
+                "prints the usage and docstring of the binding name");
+        insert!(env, "apropos", apply_apropos,
+                "(apropos str-or-regex)",
+                "a list of the bound names containing str or matching regex");
+        insert!(env, "dir", apply_dir,
+                "(dir)",
+                "prints every bound name");
+
+        let core = Scope{
+            current: Rc::new(RefCell::new(env))
+        };
+
+        core.create_namespace("user")
     }
 
     // make a clone of what we have, and pass it as the parent environment of the child
     // which will then return.
     pub fn new_scope(&self) -> Self {
 
-        let env = Rc::new(RefCell::new(Env {
-            data: HashMap::new(),
-            outer: Some(self.current.clone()),
-        }));
+        let env = Rc::new(RefCell::new(Env::new(Some(self.current.clone()))));
 
         Self {
             current: env
         }
     }
 
+    /// true when both share the same bindings
+    pub fn ptr_eq(&self, other: &Scope) -> bool {
+        Rc::ptr_eq(&self.current, &other.current)
+    }
+
+    /// the scope without keeping its bindings alive, for the closures bound in it
+    pub fn downgrade(&self) -> WeakScope {
+        WeakScope {
+            current: Rc::downgrade(&self.current)
+        }
+    }
+
+    /// a closure bound in the scope it closes over holds it weakly, see Function::bound_in
+    fn bound_here(&self, entry: LispValue) -> LispValue {
+        match entry {
+            LispValue::Function(f) => LispValue::Function(f.bound_in(self)),
+            LispValue::Macro(f) => LispValue::Macro(f.bound_in(self)),
+            other => other
+        }
+    }
 
     pub fn set(&mut self, key: String, entry: LispValue) {
         // one of many places were as_ref is used --
         // this *tricks* the Rc to correctly delegate the borrow_mut method call to the underlying refcell
         // otherwise everything is a mess.
+        let entry = self.bound_here(entry);
         self.current.as_ref().borrow_mut().insert(key, entry);
     }
 
-    pub fn find(&self, key: &String) -> Option<Rc<RefCell<Env>>> {
+    pub fn find(&self, key: &str) -> Option<Rc<RefCell<Env>>> {
         let mut env = Some(self.current.clone());
 
         while let Some(e) = env {
@@ -139,18 +681,131 @@ impl Scope {
         None
     }
 
-    pub fn get(&self, key: &String) -> Option<LispValue> {
+    /// looks key up from here outwards. a name like str/join that isn't bound as such is looked
+    /// up in the namespace str, or in the one str is an alias for.
+    pub fn get(&self, key: &str) -> Option<LispValue> {
+        match self.find(key) {
+            Some(e) => e.as_ref().borrow().get(key),
+            None => self.qualified(key).and_then(|(namespace, name)| namespace.current.as_ref().borrow().get(name))
+        }
+    }
+
+    fn qualified<'a>(&self, key: &'a str) -> Option<(Scope, &'a str)> {
+        let (prefix, name) = match key.find('/') {
+            Some(i) if i > 0 && i + 1 < key.len() && !key.starts_with(' ') => (&key[..i], &key[i + 1..]),
+            _ => return None
+        };
+
+        let namespace = match self.get(&alias_key(prefix)) {
+            Some(LispValue::String(full_name)) => full_name,
+            _ => prefix.to_string()
+        };
+
+        self.namespace(&namespace).map(|scope| (scope, name))
+    }
+
+    /// binds key in the current scope along with its documentation, a redefinition
+    /// without any drops the docs of the previous value.
+    pub fn define(&mut self, key: String, entry: LispValue, doc: Option<Doc>) {
+        let entry = self.bound_here(entry);
+        let mut current = self.current.as_ref().borrow_mut();
+
+        match doc {
+            Some(doc) => current.docs.insert(key.clone(), doc),
+            None => current.docs.remove(&key)
+        };
+
+        current.insert(key, entry);
+    }
+
+    pub fn get_doc(&self, key: &str) -> Option<Doc> {
         match self.find(key) {
-            None => None,
-            Some(e) => e.as_ref().borrow().get(key)
+            Some(e) => e.as_ref().borrow().docs.get(key).cloned(),
+            None => self.qualified(key).and_then(|(namespace, name)| namespace.current.as_ref().borrow().docs.get(name).cloned())
+        }
+    }
+
+    /// every name visible from this scope in sorted order, leaving out the
+    /// hidden bindings that start with a space.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut env = Some(self.current.clone());
+
+        while let Some(e) = env {
+            names.extend(e.as_ref().borrow().data.keys().filter(|k| !k.starts_with(' ')).cloned());
+            env = e.as_ref().borrow().outer.clone();
+        }
+
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// the names bound in this scope itself, sorted and without the hidden ones
+    pub fn own_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.current.as_ref().borrow().data.keys()
+            .filter(|k| !k.starts_with(' '))
+            .cloned()
+            .collect();
+
+        names.sort();
+        names
+    }
+
+    /// the name of the namespace this scope belongs to
+    pub fn namespace_name(&self) -> String {
+        match self.get(NAMESPACE) {
+            Some(LispValue::String(name)) => name,
+            _ => "user".to_string()
+        }
+    }
+
+    /// the scope of the namespace this scope belongs to, where refer and alias add their bindings
+    pub fn namespace_scope(&self) -> Scope {
+        match self.find(NAMESPACE) {
+            Some(e) => Scope { current: e },
+            None => self.clone().root().unwrap_or_else(|| self.clone())
         }
     }
 
+    /// the namespace called name, if it has been created
+    pub fn namespace(&self, name: &str) -> Option<Scope> {
+        let root = self.clone().root()?;
+
+        let namespace = root.current.as_ref().borrow().namespaces.get(name).cloned();
+        namespace
+    }
+
+    /// the namespace called name, created as a child of the root when it doesn't exist yet
+    /// forgets the namespace name, for a require whose file failed to load
+    pub fn remove_namespace(&self, name: &str) {
+        let root = self.clone().root().unwrap_or_else(|| self.clone());
+        root.current.as_ref().borrow_mut().namespaces.remove(name);
+    }
+
+    pub fn create_namespace(&self, name: &str) -> Scope {
+        if let Some(namespace) = self.namespace(name) {
+            return namespace
+        }
+
+        let root = self.clone().root().unwrap_or_else(|| self.clone());
+        let mut namespace = root.new_scope();
+        namespace.set(NAMESPACE.to_string(), LispValue::String(name.to_string()));
+
+        root.current.as_ref().borrow_mut().namespaces.insert(name.to_string(), namespace.clone());
+        namespace
+    }
+
+    /// lets alias/x stand for name/x in the namespace of this scope
+    pub fn alias(&self, alias: &str, name: &str) {
+        self.namespace_scope().set(alias_key(alias), LispValue::String(name.to_string()));
+    }
+
     pub fn root(&mut self) -> Option<Scope> {
         let mut env = Some(self.current.clone());
 
         while let Some(e) = env {
-            if let None = e.as_ref().borrow().outer {
+            if e.as_ref().borrow().outer.is_none() {
                 return Some(Scope {
                     current: e.clone()
                 })
@@ -161,4 +816,10 @@ impl Scope {
 
         None
     }
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+use super::ast::{LispValue, Lambda, Closure};
+use super::list::List;
+use super::map::Map;
+use super::env::{Scope, WeakScope};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// what a function runs, a builtin or a closure along with the scope it closes over
+#[derive(Clone)]
+enum Body {
+    Builtin(Lambda),
+    Closure(Closure, Captured)
+}
+
+/// a closure bound in the very scope it closes over, by a let or a def!, holds that scope weakly,
+/// otherwise the two would keep each other alive and neither would ever be dropped. it holds it
+/// strongly again as soon as it is looked up, so a closure returned out of its let keeps the let's
+/// bindings. a scope that only reaches its closure through a collection, an atom or a lazy seq
+/// still holds it strongly, so such a cycle is never dropped.
+#[derive(Clone)]
+enum Captured {
+    Strong(Scope),
+    Weak(WeakScope)
+}
+
+/// Function wraps the callable behind functions and macros together with what we know about it,
+/// defn gives it a name, anonymous lambdas and builtins don't have one. functions built from
+/// parameter lists also know their arities, builtins check their args themselves.
+#[derive(Clone)]
+pub struct Function {
+    body: Body,
+    name: Option<String>,
+    meta: Option<Rc<Map>>,
+    arities: Option<Rc<[(usize, bool)]>>,
+    // a multimethod's methods by dispatch value, shared with its closure so defmethod can add to them
+    methods: Option<Rc<RefCell<Map>>>
+}
+
+impl Function {
+    pub fn new(lambda: Lambda) -> Self {
+        Function {
+            body: Body::Builtin(lambda),
+            name: None,
+            meta: None,
+            arities: None,
+            methods: None
+        }
+    }
+
+    /// a function running closure in a child of scope
+    pub fn closure(closure: Closure, scope: &Scope) -> Self {
+        Function {
+            body: Body::Closure(closure, Captured::Strong(scope.clone())),
+            name: None,
+            meta: None,
+            arities: None,
+            methods: None
+        }
+    }
+
+    pub fn call(&self, args: &List, env: &mut Scope) -> LispValue {
+        match &self.body {
+            Body::Builtin(lambda) => lambda(args, env),
+            Body::Closure(closure, Captured::Strong(scope)) => closure(args, env, scope),
+            Body::Closure(closure, Captured::Weak(scope)) => match scope.upgrade() {
+                Some(scope) => closure(args, env, &scope),
+                None => LispValue::Error("the scope this function closes over is gone".to_string())
+            }
+        }
+    }
+
+    /// the function as it is kept when bound in scope, see Captured
+    pub fn bound_in(&self, scope: &Scope) -> Self {
+        match &self.body {
+            Body::Closure(closure, Captured::Strong(captured)) if captured.ptr_eq(scope) => Function {
+                body: Body::Closure(closure.clone(), Captured::Weak(scope.downgrade())),
+                ..self.clone()
+            },
+            _ => self.clone()
+        }
+    }
+
+    /// the function as it is handed out when looked up, holding its scope again
+    pub fn looked_up(&self) -> Self {
+        match &self.body {
+            Body::Closure(closure, Captured::Weak(captured)) => match captured.upgrade() {
+                Some(scope) => Function {
+                    body: Body::Closure(closure.clone(), Captured::Strong(scope)),
+                    ..self.clone()
+                },
+                None => self.clone()
+            },
+            _ => self.clone()
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = Some(name);
+    }
+
+    /// the number of fixed parameters of each arity and whether it takes a rest parameter
+    pub fn arities(&self) -> Option<&[(usize, bool)]> {
+        self.arities.as_deref()
+    }
+
+    pub fn set_arities(&mut self, arities: Vec<(usize, bool)>) {
+        self.arities = Some(arities.into());
+    }
+
+    /// the method table when this is a multimethod
+    pub fn methods(&self) -> Option<&Rc<RefCell<Map>>> {
+        self.methods.as_ref()
+    }
+
+    pub fn set_methods(&mut self, methods: Rc<RefCell<Map>>) {
+        self.methods = Some(methods);
+    }
+
+    pub fn meta(&self) -> Option<&Map> {
+        self.meta.as_deref()
+    }
+
+    /// a copy of the same function with meta attached, None drops the metadata
+    pub fn with_meta(&self, meta: Option<Map>) -> Self {
+        Function {
+            meta: meta.map(Rc::new),
+            ..self.clone()
+        }
+    }
+
+    /// identifies the underlying closure, for hashing
+    pub fn address(&self) -> usize {
+        match &self.body {
+            Body::Builtin(lambda) => Rc::as_ptr(lambda) as *const () as usize,
+            Body::Closure(closure, _) => Rc::as_ptr(closure) as *const () as usize
+        }
+    }
+
+    /// copies share the underlying closure, so a renamed copy is still the same function
+    pub fn ptr_eq(&self, other: &Function) -> bool {
+        self.address() == other.address()
+    }
+}
@@ -0,0 +1,224 @@
+use super::ast::LispValue;
+use super::env::Scope;
+use super::printer::pr_str;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// computes a lazy seq when it is first needed
+pub type Thunk = Rc<dyn Fn(&mut Scope) -> Next>;
+
+/// what to do once the first step of another seq is known
+pub type Then = Box<dyn FnOnce(Step, &mut Scope) -> Next>;
+
+/// what a thunk hands back. a thunk that needs the first step of another seq, like map does of the
+/// seq it maps over, asks for it with After instead of realizing it itself, so a seq mapped over a
+/// seq mapped over ... is realized in a loop in step rather than one rust call per layer.
+pub enum Next {
+    /// the seq itself: nil, a list or another lazy seq
+    Done(LispValue),
+    /// realize the first step of the seq, then carry on with it
+    After(LispValue, Then)
+}
+
+/// a realized seq is either empty or a first item followed by the rest, which is itself
+/// a seq: nil, a list or another lazy seq
+pub type Step = Option<(LispValue, LispValue)>;
+
+enum State {
+    // the scope the seq was created in, which the thunk runs in
+    Pending(Thunk, Scope),
+    Realized(Step)
+}
+
+struct Cell {
+    state: RefCell<State>
+}
+
+// dropping a long realized seq would otherwise recurse once per item
+impl Drop for Cell {
+    fn drop(&mut self) {
+        let mut next = take_rest(self.state.get_mut());
+
+        while let Some(LispValue::LazySeq(seq)) = next {
+            next = match Rc::try_unwrap(seq.cell) {
+                Ok(mut cell) => take_rest(cell.state.get_mut()),
+                Err(_) => None
+            };
+        }
+    }
+}
+
+fn take_rest(state: &mut State) -> Option<LispValue> {
+    match state {
+        State::Realized(Some((_, rest))) => Some(std::mem::replace(rest, LispValue::Nil)),
+        _ => None
+    }
+}
+
+/// LazySeq is a seq whose items are only computed once something asks for them. each step is
+/// realized at most once and remembered, so walking the same seq twice doesn't run the thunk again,
+/// and since only the steps that are asked for are realized a seq can go on forever.
+#[derive(Clone)]
+pub struct LazySeq {
+    cell: Rc<Cell>
+}
+
+impl LazySeq {
+    pub fn new(thunk: Thunk, scope: &Scope) -> Self {
+        LazySeq {
+            cell: Rc::new(Cell {
+                state: RefCell::new(State::Pending(thunk, scope.clone()))
+            })
+        }
+    }
+
+    /// an already realized seq of first followed by rest, which is how cons puts an item in front of a lazy seq
+    pub fn cons(first: LispValue, rest: LispValue) -> Self {
+        LazySeq {
+            cell: Rc::new(Cell {
+                state: RefCell::new(State::Realized(Some((first, rest))))
+            })
+        }
+    }
+
+    pub fn is_realized(&self) -> bool {
+        matches!(*self.cell.state.borrow(), State::Realized(_))
+    }
+
+    /// realizes the first step. a thunk that returns another lazy seq is followed in a loop, and a
+    /// thunk that asks for the step of another seq is put on a stack while that seq is realized, so
+    /// neither long chains nor deep nesting grow the rust stack. only a lazy-seq body that walks
+    /// other seqs itself still nests.
+    pub fn step(&self) -> Result<Step, String> {
+        // a thunk waiting on the step of another seq, with the seqs that realize to what it produces
+        struct Frame {
+            waiting: Vec<LazySeq>,
+            then: Then,
+            scope: Scope
+        }
+
+        let mut frames: Vec<Frame> = Vec::new();
+        // the seqs that realize to the step being worked out now
+        let mut waiting = Vec::new();
+        let mut seq = LispValue::LazySeq(self.clone());
+
+        loop {
+            let (next, scope) = match known_step(&seq)? {
+                Known::Pending(current, thunk, mut scope) => {
+                    waiting.push(current);
+                    (thunk(&mut scope), scope)
+                },
+                Known::Step(step) => {
+                    for seq in waiting.drain(..) {
+                        *seq.cell.state.borrow_mut() = State::Realized(step.clone());
+                    }
+
+                    match frames.pop() {
+                        None => return Ok(step),
+                        Some(Frame { waiting: w, then, mut scope }) => {
+                            waiting = w;
+                            (then(step, &mut scope), scope)
+                        }
+                    }
+                }
+            };
+
+            seq = match next {
+                Next::After(inner, then) => {
+                    frames.push(Frame { waiting: std::mem::take(&mut waiting), then, scope });
+                    inner
+                },
+                Next::Done(LispValue::Error(e)) => return Err(e),
+                Next::Done(value @ LispValue::Nil) | Next::Done(value @ LispValue::List(_))
+                    | Next::Done(value @ LispValue::LazySeq(_)) => value,
+                Next::Done(other) => return Err(format!("a lazy seq has to produce a list, a lazy seq or nil but got {}", pr_str(&other, true)))
+            };
+        }
+    }
+
+    pub fn iter(&self) -> Iter {
+        Iter {
+            next: Some(LispValue::LazySeq(self.clone()))
+        }
+    }
+
+    /// realizes the whole seq, which never returns for an infinite one
+    pub fn to_vec(&self) -> Result<Vec<LispValue>, String> {
+        self.iter().collect()
+    }
+
+    pub fn ptr_eq(&self, other: &LazySeq) -> bool {
+        Rc::ptr_eq(&self.cell, &other.cell)
+    }
+}
+
+enum Known {
+    Step(Step),
+    Pending(LazySeq, Thunk, Scope)
+}
+
+/// the step of a seq if it is known without running anything
+fn known_step(seq: &LispValue) -> Result<Known, String> {
+    match seq {
+        LispValue::Nil => Ok(Known::Step(None)),
+        LispValue::List(l) => Ok(Known::Step(l.first().map(|first| (first.clone(), LispValue::List(l.rest()))))),
+        LispValue::LazySeq(s) => match &*s.cell.state.borrow() {
+            State::Realized(step) => Ok(Known::Step(step.clone())),
+            State::Pending(thunk, scope) => Ok(Known::Pending(s.clone(), thunk.clone(), scope.clone()))
+        },
+        other => Err(format!("{} is not a seq", pr_str(other, true)))
+    }
+}
+
+/// the first item and the rest of a seq, None when it is empty. only nil, lists and lazy seqs are seqs.
+pub fn seq_step(seq: &LispValue) -> Result<Step, String> {
+    match seq {
+        LispValue::Nil => Ok(None),
+        LispValue::List(l) => Ok(l.first().map(|first| (first.clone(), LispValue::List(l.rest())))),
+        LispValue::LazySeq(s) => s.step(),
+        other => Err(format!("{} is not a seq", pr_str(other, true)))
+    }
+}
+
+/// walks a seq item by item, realizing it as it goes. a step that fails is the last thing it yields.
+pub struct Iter {
+    next: Option<LispValue>
+}
+
+impl Iter {
+    pub fn new(seq: LispValue) -> Self {
+        Iter {
+            next: Some(seq)
+        }
+    }
+}
+
+impl Iterator for Iter {
+    type Item = Result<LispValue, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match seq_step(&self.next.take()?) {
+            Err(e) => Some(Err(e)),
+            Ok(None) => None,
+            Ok(Some((first, rest))) => {
+                self.next = Some(rest);
+                Some(Ok(first))
+            }
+        }
+    }
+}
+
+/// lazy seqs are equal to lists and other lazy seqs with equal items, a seq that fails to realize
+/// isn't equal to anything
+pub fn seq_equal(a: &LispValue, b: &LispValue) -> bool {
+    let mut left = Iter::new(a.clone());
+    let mut right = Iter::new(b.clone());
+
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return true,
+            (Some(Ok(l)), Some(Ok(r))) if l == r => (),
+            _ => return false
+        }
+    }
+}
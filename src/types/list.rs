@@ -1,47 +1,232 @@
 use super::ast::{LispValue};
-use crate::reader::tokenizer::Token;
+use super::map::Map;
+use super::printer::pr_list;
 use std::ops::Index;
+use std::iter::FromIterator;
+use std::rc::Rc;
 use std::fmt::{Display, Formatter, Result};
 
+struct Node {
+    value: LispValue,
+    next: Option<Rc<Node>>
+}
+
+// the default drop would recurse once per cell, which overflows the stack on long lists
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+
+        while let Some(node) = next {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => next = node.next.take(),
+                Err(_) => break
+            }
+        }
+    }
+}
+
+/// List is a persistent list. a list made in one go (from_vec, collect, the reader) keeps its items in
+/// one shared array, so get and indexing are O(1) and rest and skip only move an offset into it.
+/// cons puts single cells in front of that array, which is O(1) and shares the list it came from;
+/// indexing into the consed cells walks them, so a list built up one cons at a time indexes in O(n).
 #[derive(Clone)]
 pub struct List {
-    items: Vec<LispValue>
+    // cells added with cons, in front of the items in back
+    front: Option<Rc<Node>>,
+    front_len: usize,
+    // the rest of the items are back[start..]
+    back: Option<Rc<[LispValue]>>,
+    start: usize,
+    len: usize,
+    // attached with with-meta, it plays no part in equality
+    meta: Option<Rc<Map>>
 }
 
-/// List is a shallow wrapper around a Vec<LispValue>, it adds a few convenience methods
 impl List {
     pub fn new() -> Self {
         List {
-            items: Vec::new()
+            front: None,
+            front_len: 0,
+            back: None,
+            start: 0,
+            len: 0,
+            meta: None
         }
     }
 
     pub fn from_vec(items: Vec<LispValue>) -> Self {
+        if items.is_empty() {
+            return List::new()
+        }
+
         List {
-            items
+            len: items.len(),
+            back: Some(items.into()),
+            ..List::new()
         }
     }
 
-    pub fn first_token(&self) -> Option<&Token> {
-        match self.items.first() {
-            Some(LispValue::Unit(a)) => {
-                Some(a.token())
+    /// a new list with value in front, sharing all of this list
+    pub fn cons(&self, value: LispValue) -> Self {
+        List {
+            front: Some(Rc::new(Node {
+                value,
+                next: self.front.clone()
+            })),
+            front_len: self.front_len + 1,
+            back: self.back.clone(),
+            start: self.start,
+            len: self.len + 1,
+            meta: None
+        }
+    }
+
+    pub fn first(&self) -> Option<&LispValue> {
+        self.get(0)
+    }
+
+    /// everything but the first item, the empty list for the empty list
+    pub fn rest(&self) -> Self {
+        self.skip(1)
+    }
+
+    /// the list without its first n items, sharing the rest
+    pub fn skip(&self, n: usize) -> Self {
+        let n = n.min(self.len);
+        let from_front = n.min(self.front_len);
+        let mut front = self.front.as_ref();
+
+        for _ in 0..from_front {
+            front = front.and_then(|node| node.next.as_ref());
+        }
+
+        List {
+            front: front.cloned(),
+            front_len: self.front_len - from_front,
+            back: self.back.clone(),
+            start: self.start + n - from_front,
+            len: self.len - n,
+            meta: None
+        }
+    }
+
+    pub fn first_symbol(&self) -> Option<&str> {
+        match self.first() {
+            Some(LispValue::Symbol(a)) => {
+                Some(a.name())
             }
             _  => None,
 
         }
     }
 
-    pub fn items(&self) -> &Vec<LispValue> {
-        &self.items
+    pub fn get(&self, index: usize) -> Option<&LispValue> {
+        if index < self.front_len {
+            self.front_iter().nth(index)
+        } else {
+            self.back_items().get(index - self.front_len)
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            front: self.front.as_deref(),
+            back: self.back_items().iter()
+        }
+    }
+
+    /// copies the items out, for code that wants to slice a list
+    pub fn to_vec(&self) -> Vec<LispValue> {
+        self.iter().cloned().collect()
     }
 
     pub fn len(&self) -> usize {
-        self.items.len()
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn meta(&self) -> Option<&Map> {
+        self.meta.as_deref()
+    }
+
+    /// a copy sharing the same items with meta attached, None drops the metadata
+    pub fn with_meta(&self, meta: Option<Map>) -> Self {
+        List {
+            meta: meta.map(Rc::new),
+            ..self.clone()
+        }
+    }
+
+    /// true when both lists share the same cells
+    pub fn ptr_eq(&self, other: &List) -> bool {
+        match (&self.front, &other.front, &self.back, &other.back) {
+            (Some(a), Some(b), _, _) => Rc::ptr_eq(a, b),
+            (None, None, Some(a), Some(b)) => Rc::ptr_eq(a, b) && self.start == other.start,
+            _ => false
+        }
     }
 
-    pub fn push(&mut self, val: LispValue) {
-        self.items.push(val)
+    fn front_iter(&self) -> Iter<'_> {
+        Iter {
+            front: self.front.as_deref(),
+            back: [].iter()
+        }
+    }
+
+    fn back_items(&self) -> &[LispValue] {
+        match &self.back {
+            Some(back) => &back[self.start..],
+            None => &[]
+        }
+    }
+}
+
+pub struct Iter<'a> {
+    front: Option<&'a Node>,
+    back: std::slice::Iter<'a, LispValue>
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a LispValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.front {
+            Some(node) => {
+                self.front = node.next.as_deref();
+                Some(&node.value)
+            },
+            None => self.back.next()
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a List {
+    type Item = &'a LispValue;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<LispValue> for List {
+    fn from_iter<I: IntoIterator<Item = LispValue>>(iter: I) -> Self {
+        List::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl Default for List {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for List {
+    fn eq(&self, other: &List) -> bool {
+        self.ptr_eq(other) || (self.len == other.len && self.iter().eq(other.iter()))
     }
 }
 
@@ -49,18 +234,48 @@ impl Index<usize> for List {
     type Output = LispValue;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.items[index]
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!("index {} out of bounds for a list of {}", index, self.len)
+        }
     }
 }
 
 impl Display for List {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "(")?;
+        write!(f, "{}", pr_list(self, false))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        for item in &self.items {
-            write!(f, "{}, ", item)?;
+    #[test]
+    fn indexing_is_constant_time() {
+        // a quadratic get would take minutes over a list this long
+        let n = 200_000;
+        let list = List::from_vec((0..n).map(LispValue::Int).collect());
+
+        for i in 0..n {
+            assert!(list[i as usize] == LispValue::Int(i));
         }
 
-        write!(f, ")")
+        assert!(list.skip(150_000).get(10) == Some(&LispValue::Int(150_010)));
+        assert!(list.get(n as usize).is_none());
+    }
+
+    #[test]
+    fn cons_in_front_of_shared_items() {
+        let list = List::from_vec(vec![LispValue::Int(2), LispValue::Int(3)]);
+        let consed = list.cons(LispValue::Int(1)).cons(LispValue::Int(0));
+
+        assert_eq!(consed.to_string(), "(0 1 2 3)");
+        assert_eq!(consed[1].to_string(), "1");
+        assert_eq!(consed[3].to_string(), "3");
+        assert_eq!(consed.skip(3).to_string(), "(3)");
+        assert!(consed.skip(2).ptr_eq(&list));
+        assert_eq!(consed.skip(9).len(), 0);
+        assert!(consed.rest() == List::from_vec(vec![LispValue::Int(1), LispValue::Int(2), LispValue::Int(3)]));
     }
 }
@@ -0,0 +1,149 @@
+use super::ast::{LispValue, hash_value};
+use rpds::{HashTrieMap, RedBlackTreeMap};
+use std::rc::Rc;
+
+/// Map is persistent: the entries sit in a red black tree keyed by when they were added, which
+/// keeps insertion order, next to a hash array mapped trie from the structural hash of a key to its
+/// entries, so any value, a list of coordinates say, works as a key. assoc and dissoc are O(log n)
+/// and share everything they don't touch with the map they came from.
+#[derive(Clone)]
+pub struct Map {
+    entries: RedBlackTreeMap<u64, (LispValue, LispValue)>,
+    index: HashTrieMap<u64, Vec<u64>>,
+    // the insertion number of the next new key
+    next: u64,
+    // attached with with-meta, it plays no part in equality
+    meta: Option<Rc<Map>>
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map {
+            entries: RedBlackTreeMap::new(),
+            index: HashTrieMap::new(),
+            next: 0,
+            meta: None
+        }
+    }
+
+    /// later values win when a key shows up more than once, the key itself stays the first one
+    pub fn from_pairs(pairs: Vec<(LispValue, LispValue)>) -> Self {
+        let mut m = Map::new();
+
+        for (key, value) in pairs {
+            m.insert(key, value);
+        }
+
+        m
+    }
+
+    fn position(&self, hash: u64, key: &LispValue) -> Option<u64> {
+        self.index.get(&hash)?.iter().copied().find(|i| self.entries.get(i).map(|(k, _)| k) == Some(key))
+    }
+
+    pub fn get(&self, key: &LispValue) -> Option<&LispValue> {
+        let i = self.position(hash_value(key), key)?;
+
+        self.entries.get(&i).map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &LispValue) -> bool {
+        self.position(hash_value(key), key).is_some()
+    }
+
+    /// a key that is already there keeps its place, and itself, so 1.0 doesn't replace 1, and
+    /// only gets the new value
+    pub fn insert(&mut self, key: LispValue, value: LispValue) {
+        let hash = hash_value(&key);
+
+        match self.position(hash, &key) {
+            Some(i) => {
+                let key = self.entries[&i].0.clone();
+                self.entries.insert_mut(i, (key, value))
+            },
+            None => {
+                let mut bucket = self.index.get(&hash).cloned().unwrap_or_default();
+                bucket.push(self.next);
+
+                self.index.insert_mut(hash, bucket);
+                self.entries.insert_mut(self.next, (key, value));
+                self.next += 1;
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &LispValue) {
+        let hash = hash_value(key);
+
+        if let Some(i) = self.position(hash, key) {
+            self.entries.remove_mut(&i);
+
+            let bucket: Vec<u64> = self.index[&hash].iter().copied().filter(|&j| j != i).collect();
+
+            if bucket.is_empty() {
+                self.index.remove_mut(&hash);
+            } else {
+                self.index.insert_mut(hash, bucket);
+            }
+        }
+    }
+
+    pub fn assoc(&self, key: LispValue, value: LispValue) -> Map {
+        let mut m = self.clone();
+        m.insert(key, value);
+
+        m
+    }
+
+    pub fn dissoc(&self, key: &LispValue) -> Map {
+        let mut m = self.clone();
+        m.remove(key);
+
+        m
+    }
+
+    pub fn meta(&self) -> Option<&Map> {
+        self.meta.as_deref()
+    }
+
+    /// a copy sharing the same entries with meta attached, None drops the metadata
+    pub fn with_meta(&self, meta: Option<Map>) -> Self {
+        Map {
+            meta: meta.map(Rc::new),
+            ..self.clone()
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(LispValue, LispValue)> {
+        self.entries.values()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &LispValue> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &LispValue> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// maps are equal when they have the same keys mapped to equal values, order doesn't matter
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
@@ -1,6 +1,14 @@
 pub mod ast;
-pub mod unit;
+pub mod symbol;
 pub mod list;
+pub mod map;
+pub mod numeric;
+pub mod set;
+pub mod lazy;
+pub mod record;
+pub mod function;
 pub mod env;
+pub mod pattern;
+pub mod printer;
 mod reader_macros;
 use super::reader;
@@ -0,0 +1,67 @@
+use super::ast::LispValue;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+use std::cmp::Ordering;
+
+/// big ints that fit back into an i64 are demoted, so small results stay on the fast path
+pub fn normalize(b: BigInt) -> LispValue {
+    match b.to_i64() {
+        Some(i) => LispValue::Int(i),
+        None => LispValue::BigInt(b)
+    }
+}
+
+/// ratios that reduce to a whole number become ints again
+pub fn normalize_ratio(r: BigRational) -> LispValue {
+    if r.is_integer() {
+        normalize(r.to_integer())
+    } else {
+        LispValue::Ratio(r)
+    }
+}
+
+pub fn to_float(value: &LispValue) -> Option<f64> {
+    match value {
+        LispValue::Int(i) => Some(*i as f64),
+        LispValue::BigInt(b) => b.to_f64(),
+        LispValue::Ratio(r) => r.to_f64(),
+        LispValue::Float(f) => Some(*f),
+        _ => None
+    }
+}
+
+/// the exact numbers (ints, big ints and ratios) all convert to a ratio without losing anything
+pub fn to_ratio(value: &LispValue) -> Option<BigRational> {
+    match value {
+        LispValue::Int(i) => Some(BigRational::from_integer(BigInt::from(*i))),
+        LispValue::BigInt(b) => Some(BigRational::from_integer(b.clone())),
+        LispValue::Ratio(r) => Some(r.clone()),
+        _ => None
+    }
+}
+
+/// orders any two numbers by their exact value, so nothing gets rounded: a finite float is
+/// compared as the ratio it stands for, which keeps (= 9007199254740993 9007199254740992.0) false.
+/// only infinities and NaN fall back to comparing floats.
+pub fn numeric_ordering(l: &LispValue, r: &LispValue) -> Option<Ordering> {
+    match (l, r) {
+        (LispValue::Int(a), LispValue::Int(b)) => Some(a.cmp(b)),
+        (LispValue::Float(a), LispValue::Float(b)) => a.partial_cmp(b),
+        _ => match (exact_value(l), exact_value(r)) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => match (to_float(l), to_float(r)) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => None
+            }
+        }
+    }
+}
+
+/// the exact value of a number, None for infinities and NaN
+pub fn exact_value(value: &LispValue) -> Option<BigRational> {
+    match value {
+        LispValue::Float(f) => BigRational::from_float(*f),
+        other => to_ratio(other)
+    }
+}
@@ -0,0 +1,42 @@
+use regex::Regex;
+use std::fmt::{Display, Formatter, Result};
+
+/// Pattern is a compiled regular expression. We keep an anchored copy next to it
+/// so whole-string matches (re-matches) don't need to recompile on every call.
+#[derive(Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+    anchored: Regex
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> std::result::Result<Self, regex::Error> {
+        let regex = Regex::new(source)?;
+        let anchored = Regex::new(&format!(r"\A(?:{})\z", source))?;
+
+        Ok(Pattern {
+            source: source.to_string(),
+            regex,
+            anchored
+        })
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    pub fn anchored(&self) -> &Regex {
+        &self.anchored
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "#\"{}\"", self.source)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::types::ast::LispValue;
+use crate::types::list::List;
+use crate::types::map::Map;
+use crate::reader::tokenizer::CHAR_NAMES;
+
+/// escape a string so that reading it back in produces the same string
+pub fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c)
+        }
+    }
+
+    out
+}
+
+/// the reader syntax of a character, named characters and other control characters
+/// can't follow the backslash as they are.
+pub fn escape_char(c: char) -> String {
+    match CHAR_NAMES.iter().find(|(_, named)| *named == c) {
+        Some((name, _)) => format!("\\{}", name),
+        None if c.is_control() => format!("\\u{:04x}", c as u32),
+        None => format!("\\{}", c)
+    }
+}
+
+/// printing a lazy seq realizes at most this many items and ends with ... when there are more,
+/// so printing or calling str on an infinite seq doesn't hang
+pub const LAZY_PRINT_LENGTH: usize = 1000;
+
+/// pr_str has two modes: readably (used by pr-str, prn and the repl) prints values the way the reader
+/// expects them, while the display mode (str, println) prints strings raw.
+pub fn pr_str(value: &LispValue, readably: bool) -> String {
+    match value {
+        LispValue::Int(i) => i.to_string(),
+        LispValue::BigInt(b) => b.to_string(),
+        LispValue::Ratio(r) => r.to_string(),
+        // debug formatting keeps the trailing .0, otherwise 1.0 would read back as an int
+        LispValue::Float(float) => format!("{:?}", float),
+        LispValue::Error(message) => format!("error - {}", message),
+        LispValue::Boolean(b) => b.to_string(),
+        LispValue::Nil => "nil".to_string(),
+        LispValue::Function(f) => match f.name() {
+            Some(name) => format!("#<fn {}>", name),
+            None => "#<lambda>".to_string()
+        },
+        LispValue::String(s) => {
+            if readably {
+                format!("\"{}\"", escape_string(s))
+            } else {
+                s.clone()
+            }
+        },
+        LispValue::Char(c) => {
+            if readably {
+                escape_char(*c)
+            } else {
+                c.to_string()
+            }
+        },
+        LispValue::Keyword(k) => format!(":{}", k),
+        LispValue::List(l) => pr_list(l, readably),
+        LispValue::LazySeq(s) => match s.iter().take(LAZY_PRINT_LENGTH + 1).collect::<Result<Vec<LispValue>, String>>() {
+            Ok(items) if items.len() > LAZY_PRINT_LENGTH => format!("({} ...)", pr_seq(&items[..LAZY_PRINT_LENGTH], readably, " ")),
+            Ok(items) => format!("({})", pr_seq(&items, readably, " ")),
+            Err(e) => pr_str(&LispValue::Error(e), readably)
+        },
+        LispValue::Map(m) => pr_map(m, readably),
+        LispValue::Record(r) => format!("#{}{}", r.name(), pr_map(r.fields(), readably)),
+        LispValue::Set(s) => format!("#{{{}}}", s.iter().map(|item| pr_str(item, readably)).collect::<Vec<String>>().join(" ")),
+        LispValue::Symbol(a) => a.name().to_string(),
+        LispValue::Atom(b) => format!("Atom <{}>", pr_str(&b.as_ref().borrow(), readably)),
+        LispValue::Macro(f) => match f.name() {
+            Some(name) => format!("#<macro {}>", name),
+            None => "#<macro>".to_string()
+        },
+        LispValue::Regex(p) => p.to_string(),
+        LispValue::Recur(_) => "#<recur>".to_string()
+    }
+}
+
+pub fn pr_seq<'a>(values: impl IntoIterator<Item = &'a LispValue>, readably: bool, separator: &str) -> String {
+    values.into_iter()
+        .map(|value| pr_str(value, readably))
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+pub fn pr_list(list: &List, readably: bool) -> String {
+    format!("({})", pr_seq(list, readably, " "))
+}
+
+pub fn pr_map(map: &Map, readably: bool) -> String {
+    let entries = map.iter()
+        .map(|(key, value)| format!("{} {}", pr_str(key, readably), pr_str(value, readably)))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!("{{{}}}", entries)
+}
+
+#[cfg(test)]
+mod test {
+    use super::pr_str;
+    use crate::types::ast::LispValue;
+    use crate::types::list::List;
+    use crate::types::env::Scope;
+    use crate::reader::tokenizer::Tokenizer;
+    use crate::exec::core_utils::read_string;
+    use crate::exec::eval::eval_ast;
+
+    #[test]
+    fn lists_are_space_separated() {
+        let l = List::from_vec(vec![LispValue::Int(1), LispValue::Float(2.0), LispValue::Nil]);
+
+        assert_eq!(pr_str(&LispValue::List(l), true), "(1 2.0 nil)");
+        assert_eq!(pr_str(&LispValue::List(List::new()), true), "()");
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let original = "say \"hi\"\n\tback\\slash".to_string();
+        let printed = pr_str(&LispValue::String(original.clone()), true);
+
+        assert_eq!(printed, "\"say \\\"hi\\\"\\n\\tback\\\\slash\"");
+        assert_eq!(pr_str(&LispValue::String(original.clone()), false), original);
+
+        let mut env = Scope::new();
+        match eval_ast(&read_string(&Tokenizer::new(), printed), &mut env) {
+            LispValue::String(s) => assert_eq!(s, original),
+            _ => panic!("printed string did not read back as a string")
+        }
+    }
+}
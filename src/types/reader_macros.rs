@@ -1,34 +1,52 @@
 use crate::reader::parser::Parser;
 use crate::types::ast::{LispValue, read_form};
-use crate::reader::tokenizer::{TokenType, Token};
 use crate::types::list::List;
-use crate::types::unit::Unit;
+use crate::types::map::Map;
+use crate::types::symbol::Symbol;
 
 
-fn list_with_token(token_text: &str) -> List {
-    let mut l = List::new();
-    let token = Token::new(token_text.to_string(), TokenType::Symbol);
+fn list_with_token(token_text: &str, args: Vec<LispValue>) -> List {
+    let mut items = vec![LispValue::Symbol(Symbol::new(token_text))];
+    items.extend(args);
 
-    l.push(LispValue::Unit(Unit::new(token)));
-
-    l
+    List::from_vec(items)
 }
 
 pub (super) fn at_macro(parser: &mut Parser) -> LispValue {
     parser.next();
-    let mut l = list_with_token("deref");
+    LispValue::List(list_with_token("deref", vec![read_form(parser)]))
+}
 
-    l.push(read_form(parser));
+pub (super) fn quote_macro(parser: &mut Parser) -> LispValue {
+    parser.next();
 
-    LispValue::List(l)
+    LispValue::List(list_with_token("quote", vec![read_form(parser)]))
 }
 
-pub (super) fn quote_macro(parser: &mut Parser) -> LispValue {
+/// ^meta form reads as (with-meta form meta). besides a map, meta can be a keyword which is
+/// short for {:keyword true}, or a symbol or string which is short for {:tag ...}.
+pub (super) fn meta_macro(parser: &mut Parser) -> LispValue {
     parser.next();
 
-    let mut l = list_with_token("quote");
+    if parser.peek().is_none() {
+        return LispValue::Error("^ needs metadata followed by a form".to_string())
+    }
 
-    l.push(read_form(parser));
+    let meta = match read_form(parser) {
+        LispValue::Keyword(k) => LispValue::Map(Map::from_pairs(vec![(LispValue::Keyword(k), LispValue::Boolean(true))])),
+        tag @ LispValue::Symbol(_) | tag @ LispValue::String(_) => {
+            let quoted = list_with_token("quote", vec![tag]);
 
-    LispValue::List(l)
-}
\ No newline at end of file
+            LispValue::Map(Map::from_pairs(vec![(LispValue::Keyword("tag".to_string()), LispValue::List(quoted))]))
+        },
+        meta => meta
+    };
+
+    if parser.peek().is_none() {
+        return LispValue::Error("^ needs metadata followed by a form".to_string())
+    }
+
+    let form = read_form(parser);
+
+    LispValue::List(list_with_token("with-meta", vec![form, meta]))
+}
@@ -0,0 +1,51 @@
+use super::ast::LispValue;
+use super::map::Map;
+use std::rc::Rc;
+
+/// Record is an instance of a type made with defrecord: the name of the type and its fields, keyed
+/// by keyword in the order they were declared. records are only equal to records of the same type,
+/// never to a map with the same entries.
+#[derive(Clone)]
+pub struct Record {
+    name: Rc<str>,
+    fields: Map
+}
+
+impl Record {
+    pub fn new(name: &str, fields: Map) -> Self {
+        Record {
+            name: Rc::from(name),
+            fields
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &Map {
+        &self.fields
+    }
+
+    pub fn get(&self, field: &LispValue) -> Option<&LispValue> {
+        self.fields.get(field)
+    }
+
+    /// a copy with field set to value, None when the type has no such field
+    pub fn assoc(&self, field: LispValue, value: LispValue) -> Option<Record> {
+        if !self.fields.contains_key(&field) {
+            return None
+        }
+
+        Some(Record {
+            name: self.name.clone(),
+            fields: self.fields.assoc(field, value)
+        })
+    }
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Record) -> bool {
+        self.name == other.name && self.fields == other.fields
+    }
+}
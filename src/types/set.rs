@@ -0,0 +1,118 @@
+use super::ast::{LispValue, hash_value};
+use super::map::Map;
+use rpds::{HashTrieMap, RedBlackTreeMap};
+use std::rc::Rc;
+
+/// Set is persistent like Map: the elements sit in a red black tree keyed by when they were added,
+/// next to a hash array mapped trie from the structural hash of an element to where it sits, so
+/// membership tests don't have to scan and adding or removing shares the rest of the set.
+#[derive(Clone)]
+pub struct Set {
+    items: RedBlackTreeMap<u64, LispValue>,
+    index: HashTrieMap<u64, Vec<u64>>,
+    // the insertion number of the next new element
+    next: u64,
+    // attached with with-meta, it plays no part in equality
+    meta: Option<Rc<Map>>
+}
+
+impl Set {
+    pub fn new() -> Self {
+        Set {
+            items: RedBlackTreeMap::new(),
+            index: HashTrieMap::new(),
+            next: 0,
+            meta: None
+        }
+    }
+
+    /// duplicates after the first are dropped
+    pub fn from_vec(items: Vec<LispValue>) -> Self {
+        let mut s = Set::new();
+
+        for item in items {
+            s.insert(item);
+        }
+
+        s
+    }
+
+    fn position(&self, hash: u64, value: &LispValue) -> Option<u64> {
+        self.index.get(&hash)?.iter().copied().find(|i| self.items.get(i) == Some(value))
+    }
+
+    pub fn contains(&self, value: &LispValue) -> bool {
+        self.position(hash_value(value), value).is_some()
+    }
+
+    /// adds value unless an equal element is already in the set, true when it was added
+    pub fn insert(&mut self, value: LispValue) -> bool {
+        let hash = hash_value(&value);
+
+        if self.position(hash, &value).is_some() {
+            return false
+        }
+
+        let mut bucket = self.index.get(&hash).cloned().unwrap_or_default();
+        bucket.push(self.next);
+
+        self.index.insert_mut(hash, bucket);
+        self.items.insert_mut(self.next, value);
+        self.next += 1;
+
+        true
+    }
+
+    pub fn remove(&mut self, value: &LispValue) {
+        let hash = hash_value(value);
+
+        if let Some(i) = self.position(hash, value) {
+            self.items.remove_mut(&i);
+
+            let bucket: Vec<u64> = self.index[&hash].iter().copied().filter(|&j| j != i).collect();
+
+            if bucket.is_empty() {
+                self.index.remove_mut(&hash);
+            } else {
+                self.index.insert_mut(hash, bucket);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LispValue> {
+        self.items.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn meta(&self) -> Option<&Map> {
+        self.meta.as_deref()
+    }
+
+    /// a copy sharing the same elements with meta attached, None drops the metadata
+    pub fn with_meta(&self, meta: Option<Map>) -> Self {
+        Set {
+            meta: meta.map(Rc::new),
+            ..self.clone()
+        }
+    }
+}
+
+impl Default for Set {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// sets are equal when they have the same elements, order doesn't matter
+impl PartialEq for Set {
+    fn eq(&self, other: &Set) -> bool {
+        self.len() == other.len() && self.iter().all(|item| other.contains(item))
+    }
+}
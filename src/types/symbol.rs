@@ -0,0 +1,26 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// Symbol is a name, read from source or made at runtime with symbol and gensym.
+/// evaluating one looks the name up in the scope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    name: String
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Symbol {
+        Symbol {
+            name: name.to_string()
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.name)
+    }
+}